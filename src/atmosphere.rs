@@ -0,0 +1,166 @@
+// src/atmosphere.rs
+// Capa de aire alrededor de un cuerpo con dispersión simple de Rayleigh (moléculas,
+// azul) y Mie (aerosoles, halo blanco hacia delante): limbo azulado, terminador
+// rojizo y atardeceres cuando se mira de canto.
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::noise::smoothstep;
+use crate::sphere::sphere_span;
+use crate::scene::light_falloff;
+
+/// Muestras a lo largo del rayo de vista y del rayo hacia cada luz.
+const VIEW_SAMPLES: usize = 16;
+const LIGHT_SAMPLES: usize = 8;
+
+/// Los aerosoles también absorben: su extinción es algo mayor que su dispersión.
+const MIE_EXTINCTION: f32 = 1.1;
+
+/// Atmósfera esférica con densidad que cae exponencialmente con la altura.
+/// Todas las longitudes están en unidades del mundo.
+#[derive(Clone, Debug)]
+pub struct Atmosphere {
+    /// centro del planeta (ver `Body::set_position`)
+    pub center: Vec3,
+    /// radio del suelo (densidad máxima) y del borde exterior de la capa
+    pub planet_radius: f32,
+    pub radius: f32,
+    /// coeficientes de dispersión a ras de suelo, por unidad de longitud
+    pub rayleigh: Vec3,
+    pub mie: f32,
+    /// alturas de escala: la densidad cae como e^(-h / H)
+    pub rayleigh_height: f32,
+    pub mie_height: f32,
+    /// anisotropía de Mie, en (-1, 1); > 0 dispersa hacia delante
+    pub mie_g: f32,
+}
+
+/// Una luz vista desde la atmósfera, con la emisión ya evaluada para el frame.
+#[derive(Copy, Clone, Debug)]
+pub struct Sun {
+    pub center: Vec3,
+    pub radius: f32,
+    pub emission: Vec3,
+}
+
+impl Atmosphere {
+    /// Tramo de `ray` dentro de la capa, recortado a [0, t_max].
+    fn span(&self, ray: &Ray, t_max: f32) -> Option<(f32, f32)> {
+        let (t0, t1) = sphere_span(ray, self.center, self.radius)?;
+        let (t0, t1) = (t0.max(0.0), t1.min(t_max));
+        (t0 < t1).then_some((t0, t1))
+    }
+
+    /// Densidad relativa (Rayleigh, Mie) en `p`.
+    fn density(&self, p: Vec3) -> (f32, f32) {
+        let h = ((p - self.center).length() - self.planet_radius).max(0.0);
+        ((-h / self.rayleigh_height).exp(), (-h / self.mie_height).exp())
+    }
+
+    /// Profundidad óptica por canal a partir de las densidades integradas.
+    fn extinction(&self, (rayleigh, mie): (f32, f32)) -> Vec3 {
+        self.rayleigh * rayleigh + Vec3::new(1.0, 1.0, 1.0) * (self.mie * MIE_EXTINCTION * mie)
+    }
+
+    /// Densidades integradas entre `ray.at(t0)` y `ray.at(t1)` (regla del punto medio).
+    fn optical_depth(&self, ray: &Ray, t0: f32, t1: f32, samples: usize) -> (f32, f32) {
+        let ds = (t1 - t0) / samples as f32;
+        let (mut r, mut m) = (0.0, 0.0);
+        for i in 0..samples {
+            let (dr, dm) = self.density(ray.at(t0 + (i as f32 + 0.5) * ds));
+            r += dr * ds;
+            m += dm * ds;
+        }
+        (r, m)
+    }
+
+    /// Fracción de luz, por canal, que sobrevive al cruzar la capa a lo largo
+    /// de `ray` durante `dist`. Tiñe de rojo la luz que llega rasante.
+    pub fn transmittance(&self, ray: &Ray, dist: f32) -> Vec3 {
+        let Some((t0, t1)) = self.span(ray, dist) else { return Vec3::new(1.0, 1.0, 1.0); };
+        exp(-self.extinction(self.optical_depth(ray, t0, t1, LIGHT_SAMPLES)))
+    }
+
+    /// Luz que llega por `ray` después de atravesar la capa: `behind` (lo que hay
+    /// en `t_end`, o el fondo con `t_end` infinito) atenuado, más la luz de `suns`
+    /// dispersada hacia la cámara por el camino.
+    pub fn scatter(&self, ray: &Ray, t_end: f32, behind: Vec3, suns: &[Sun]) -> Vec3 {
+        let Some((t0, t1)) = self.span(ray, t_end) else { return behind; };
+        let ds = (t1 - t0) / VIEW_SAMPLES as f32;
+
+        let mut view_depth = (0.0, 0.0);
+        let mut in_rayleigh = Vec3::zero();
+        let mut in_mie = Vec3::zero();
+        for i in 0..VIEW_SAMPLES {
+            let p = ray.at(t0 + (i as f32 + 0.5) * ds);
+            let (dr, dm) = self.density(p);
+            // profundidad hasta el centro de la muestra
+            let to_sample = (view_depth.0 + 0.5 * dr * ds, view_depth.1 + 0.5 * dm * ds);
+            view_depth = (view_depth.0 + dr * ds, view_depth.1 + dm * ds);
+
+            for sun in suns {
+                let Some((visible, towards, dist)) = self.sun_from(p, sun) else { continue; };
+                let exit = sphere_span(&towards, self.center, self.radius).map_or(0.0, |(_, b)| b.min(dist));
+                let sun_depth = self.optical_depth(&towards, 0.0, exit.max(0.0), LIGHT_SAMPLES);
+                let depth = (to_sample.0 + sun_depth.0, to_sample.1 + sun_depth.1);
+                let light = sun.emission * (visible * light_falloff(dist)) * exp(-self.extinction(depth));
+
+                let mu = ray.dir.dot(towards.dir);
+                in_rayleigh = in_rayleigh + light * (dr * rayleigh_phase(mu));
+                in_mie = in_mie + light * (dm * mie_phase(mu, self.mie_g));
+            }
+        }
+
+        let inscatter = (self.rayleigh * in_rayleigh + in_mie * self.mie) * ds;
+        behind * exp(-self.extinction(view_depth)) + inscatter
+    }
+
+    /// Cuánto del disco de `sun` asoma sobre el horizonte del planeta visto
+    /// desde `p`, el rayo por el que llega su luz y la distancia al sol.
+    ///
+    /// Las estrellas de la escena están cerca y son grandes, así que se tiene en
+    /// cuenta su tamaño: con el centro ya bajo el horizonte la luz llega rasante
+    /// desde el borde del disco, que es justo la que enrojece en los atardeceres.
+    fn sun_from(&self, p: Vec3, sun: &Sun) -> Option<(f32, Ray, f32)> {
+        let to_sun = sun.center - p;
+        let dist = to_sun.length();
+        let w = to_sun / dist;
+        let to_planet = self.center - p;
+        let r = to_planet.length();
+        let down = to_planet / r;
+
+        // ángulos desde la vertical (hacia el centro del planeta): el horizonte
+        // está a `horizon`, el sol a `theta` y su disco mide `spread` de radio
+        let horizon = (self.planet_radius / r).min(1.0).asin();
+        let theta = w.dot(down).clamp(-1.0, 1.0).acos();
+        let spread = (sun.radius / dist).min(1.0).asin();
+        let elevation = theta - horizon;
+        if elevation <= -spread { return None; }
+
+        let visible = smoothstep(-spread, spread, elevation);
+        if elevation >= 0.0 {
+            return Some((visible, Ray::new(p, w), dist));
+        }
+        let side = (w - down * w.dot(down)).normalize();
+        let grazing = down * horizon.cos() + side * horizon.sin();
+        Some((visible, Ray::new(p, grazing), dist))
+    }
+}
+
+// Las fases van multiplicadas por pi, igual que el albedo de las superficies
+// (que se ilumina con albedo * coseno, sin el 1/pi), para que aire y suelo
+// tengan brillos comparables bajo la misma luz.
+
+fn rayleigh_phase(mu: f32) -> f32 {
+    3.0 / 16.0 * (1.0 + mu * mu)
+}
+
+/// Cornette-Shanks: Henyey-Greenstein con el término (1 + mu²) de Rayleigh.
+fn mie_phase(mu: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    let denom = (1.0 + g2 - 2.0 * g * mu).max(1e-4).powf(1.5);
+    3.0 * (1.0 - g2) * (1.0 + mu * mu) / (8.0 * (2.0 + g2) * denom)
+}
+
+fn exp(v: Vec3) -> Vec3 {
+    Vec3::new(v.x.exp(), v.y.exp(), v.z.exp())
+}
@@ -0,0 +1,68 @@
+// src/body.rs
+use crate::vec3::Vec3;
+use crate::sphere::Sphere;
+use crate::orbit::Orbit;
+use crate::ring::Ring;
+use crate::atmosphere::Atmosphere;
+use crate::clouds::Clouds;
+
+/// Identificador estable de un cuerpo: su posición en `Scene::bodies`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BodyId(pub usize);
+
+/// Nodo del grafo de escena: una esfera con nombre, su padre y lo que lleva consigo.
+pub struct Body {
+    pub name: String,
+    pub parent: Option<BodyId>,
+    pub children: Vec<BodyId>,
+    /// posición relativa al padre cuando no hay órbita (absoluta si no hay padre)
+    pub local: Vec3,
+    pub orbit: Option<Orbit>,
+    /// masa para el modo de N cuerpos (0 = partícula de prueba)
+    pub mass: f32,
+    /// velocidad inicial relativa al padre; si falta se deriva de la órbita
+    pub velocity: Option<Vec3>,
+    /// si es true, el meridiano 0 siempre mira al padre (rotación sincrónica)
+    pub tidal_lock: bool,
+    pub ring: Option<Ring>,
+    pub clouds: Option<Clouds>,
+    pub atmosphere: Option<Atmosphere>,
+    /// geometría y shader; `sphere.center` es la posición en el mundo ya compuesta
+    pub sphere: Sphere,
+}
+
+impl Body {
+    /// Mueve el cuerpo y todo lo que lleva pegado: anillo, nubes y atmósfera
+    /// siempre van centrados en la esfera.
+    pub fn set_position(&mut self, pos: Vec3) {
+        self.sphere.center = pos;
+        if let Some(ring) = &mut self.ring {
+            ring.center = pos;
+        }
+        if let Some(clouds) = &mut self.clouds {
+            clouds.center = pos;
+        }
+        if let Some(atmosphere) = &mut self.atmosphere {
+            atmosphere.center = pos;
+        }
+    }
+
+    /// Posición relativa al padre en el instante `t`.
+    pub fn local_position(&self, t: f32) -> Vec3 {
+        match &self.orbit {
+            Some(orbit) => self.local + orbit.offset_at(t),
+            None => self.local,
+        }
+    }
+
+    /// Velocidad relativa al padre en `t` según la órbita (diferencia central).
+    pub fn local_velocity(&self, t: f32) -> Vec3 {
+        match &self.orbit {
+            Some(orbit) => {
+                let h = orbit.period * 1e-3;
+                (orbit.offset_at(t + h) - orbit.offset_at(t - h)) / (2.0 * h)
+            }
+            None => Vec3::zero(),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// src/cli.rs
+// Línea de comandos: `Espacio [view|render|export|preview] [opciones] [escena.toml]`
+use std::path::PathBuf;
+
+use espacio::vec3::Vec3;
+use espacio::export::FrameRange;
+use espacio::video::StreamFormat;
+
+pub const USAGE: &str = "\
+uso:
+  Espacio [view] [opciones] [escena.toml]          ventana interactiva
+  Espacio render [opciones] [escena.toml] -o F     un frame a F (.png o .ppm), sin ventana
+  Espacio export [opciones] [escena.toml] -o F     animación a F: .gif, .apng, vídeo .y4m/.rgb,
+                                                   un patrón numerado como frames/f_%04d.png
+                                                   o - para mandar Y4M por stdout (a ffmpeg)
+  Espacio preview [opciones] [escena.toml] -o DIR  una miniatura cuadrada por cuerpo, de frente,
+                                                   en DIR/<nombre>.png
+
+opciones:
+  --size WxH              resolución (por defecto 1024x640; en preview N o NxN, 512)
+  --camera X,Y,Z          posición de la cámara
+  --look-at X,Y,Z         punto al que mira la cámara
+  --fov F                 campo de visión vertical en radianes
+  --shadow-samples N      rayos de sombra por estrella
+  --time T                instante a renderizar en segundos (render, preview; por defecto 0)
+  --body NOMBRE           sólo ese cuerpo (preview)
+  --start T               inicio de la animación en segundos (export; por defecto 0)
+  --end T                 fin de la animación, alternativa a --frames (export)
+  --frames N              número de frames (export; por defecto 120)
+  --fps F                 frames por segundo (export; por defecto 30)
+  --format y4m|rgb        fuerza un flujo de vídeo Y4M o RGB24 crudo (export)
+  -o, --output F          archivo o carpeta de salida (render, export, preview)
+  -h, --help              esta ayuda";
+
+/// Opciones comunes a todos los modos: qué escena cargar y cómo mirarla.
+pub struct Options {
+    pub scene: Option<PathBuf>,
+    pub width: usize,
+    pub height: usize,
+    pub camera: Option<Vec3>,
+    pub look_at: Option<Vec3>,
+    pub fov: Option<f32>,
+    pub shadow_samples: Option<u32>,
+}
+
+pub enum Command {
+    Help,
+    View(Options),
+    Render { opts: Options, output: PathBuf, time: f32 },
+    Export { opts: Options, output: PathBuf, range: FrameRange, format: Option<StreamFormat> },
+    Preview { opts: Options, output: PathBuf, time: f32, body: Option<String>, size: usize },
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    let mode = match args.peek().map(String::as_str) {
+        Some("view") | Some("render") | Some("export") | Some("preview") => args.next(),
+        _ => None,
+    };
+
+    let mut opts = Options {
+        scene: None,
+        width: 1024,
+        height: 640,
+        camera: None,
+        look_at: None,
+        fov: None,
+        shadow_samples: None,
+    };
+    let mut output: Option<PathBuf> = None;
+    let mut time: Option<f32> = None;
+    let mut start: Option<f32> = None;
+    let mut end: Option<f32> = None;
+    let mut frames: Option<usize> = None;
+    let mut fps: Option<f32> = None;
+    let mut format: Option<StreamFormat> = None;
+    let mut body: Option<String> = None;
+    let mut sized = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("falta el valor de {}", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--size" => {
+                let v = value()?;
+                // un solo número es un cuadrado
+                let (w, h) = v.split_once('x').unwrap_or((&v, &v));
+                opts.width = parse_num(w, "--size")?;
+                opts.height = parse_num(h, "--size")?;
+                if opts.width == 0 || opts.height == 0 {
+                    return Err("--size no puede ser 0".into());
+                }
+                sized = true;
+            }
+            "--camera" => opts.camera = Some(parse_vec3(&value()?, "--camera")?),
+            "--look-at" => opts.look_at = Some(parse_vec3(&value()?, "--look-at")?),
+            "--fov" => opts.fov = Some(parse_float(&value()?, "--fov")?),
+            "--shadow-samples" => {
+                let n = parse_num(&value()?, "--shadow-samples")?;
+                if n == 0 {
+                    return Err("--shadow-samples debe ser al menos 1".into());
+                }
+                opts.shadow_samples = Some(n);
+            }
+            "--time" => time = Some(parse_float(&value()?, "--time")?),
+            "--start" => start = Some(parse_float(&value()?, "--start")?),
+            "--end" => end = Some(parse_float(&value()?, "--end")?),
+            "--frames" => frames = Some(parse_num(&value()?, "--frames")?),
+            "--fps" => fps = Some(parse_float(&value()?, "--fps")?),
+            "--format" => format = Some(value()?.parse()?),
+            "--body" => body = Some(value()?),
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            _ if arg.starts_with('-') => return Err(format!("opción desconocida: {}", arg)),
+            _ if opts.scene.is_none() => opts.scene = Some(PathBuf::from(arg)),
+            _ => return Err(format!("argumento de más: {}", arg)),
+        }
+    }
+
+    let animated = start.is_some() || end.is_some() || frames.is_some() || fps.is_some() || format.is_some();
+    if body.is_some() && mode.as_deref() != Some("preview") {
+        return Err("--body sólo tiene sentido con preview".into());
+    }
+    match mode.as_deref() {
+        Some("render") => {
+            if animated {
+                return Err("--start, --end, --frames, --fps y --format son de export".into());
+            }
+            let output = output.ok_or("render necesita -o <archivo>")?;
+            Ok(Command::Render { opts, output, time: time.unwrap_or(0.0) })
+        }
+        Some("export") => {
+            if time.is_some() {
+                return Err("export usa --start/--end en vez de --time".into());
+            }
+            let output = output.ok_or("export necesita -o <archivo>")?;
+            let range = frame_range(start, end, frames, fps)?;
+            Ok(Command::Export { opts, output, range, format })
+        }
+        Some("preview") => {
+            if animated {
+                return Err("--start, --end, --frames, --fps y --format son de export".into());
+            }
+            let output = output.ok_or("preview necesita -o <carpeta>")?;
+            let size = if sized { opts.width.min(opts.height) } else { 512 };
+            Ok(Command::Preview { opts, output, time: time.unwrap_or(0.0), body, size })
+        }
+        _ => {
+            if output.is_some() || time.is_some() || animated {
+                return Err("-o, --time y las opciones de animación no sirven en la ventana".into());
+            }
+            Ok(Command::View(opts))
+        }
+    }
+}
+
+fn frame_range(
+    start: Option<f32>,
+    end: Option<f32>,
+    frames: Option<usize>,
+    fps: Option<f32>,
+) -> Result<FrameRange, String> {
+    let start = start.unwrap_or(0.0);
+    let fps = fps.unwrap_or(30.0);
+    if !(fps > 0.0 && fps.is_finite()) {
+        return Err("--fps debe ser positivo".into());
+    }
+    let frames = match (frames, end) {
+        (Some(_), Some(_)) => return Err("usa --frames o --end, no ambos".into()),
+        (Some(n), None) => n,
+        (None, Some(end)) if end > start => ((end - start) * fps).round() as usize,
+        (None, Some(_)) => return Err("--end debe ser posterior a --start".into()),
+        (None, None) => 120,
+    };
+    if frames == 0 {
+        return Err("la animación necesita al menos un frame".into());
+    }
+    Ok(FrameRange { start, fps, frames })
+}
+
+fn parse_num<T: std::str::FromStr>(s: &str, flag: &str) -> Result<T, String> {
+    s.trim().parse().map_err(|_| format!("{}: valor inválido '{}'", flag, s))
+}
+
+/// Como `parse_num`, pero rechaza `nan` e `inf`, que `f32::from_str` acepta.
+fn parse_float(s: &str, flag: &str) -> Result<f32, String> {
+    let v: f32 = parse_num(s, flag)?;
+    if !v.is_finite() {
+        return Err(format!("{}: valor inválido '{}'", flag, s));
+    }
+    Ok(v)
+}
+
+fn parse_vec3(s: &str, flag: &str) -> Result<Vec3, String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 3 {
+        return Err(format!("{} espera X,Y,Z, no '{}'", flag, s));
+    }
+    Ok(Vec3::new(parse_float(parts[0], flag)?, parse_float(parts[1], flag)?, parse_float(parts[2], flag)?))
+}
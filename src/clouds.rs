@@ -0,0 +1,112 @@
+// src/clouds.rs
+// Capa de nubes: una esfera translúcida algo por encima del suelo que gira a su
+// propio ritmo, deja ver la superficie entre los claros y le proyecta sombras.
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::noise::{smoothstep, Noise};
+use crate::hittable::{Hit, Hittable};
+use crate::sphere::{sphere_span, spin_frame};
+
+/// Octavas del ruido de las nubes; la deformación usa las mismas.
+const OCTAVES: usize = 3;
+/// Suma de amplitudes de `fbm4` con esas octavas, para llevarlo a [0, 1].
+const FBM_RANGE: f32 = 1.75;
+
+pub struct Clouds {
+    /// el del cuerpo, como en `Ring`
+    pub center: Vec3,
+    pub radius: f32,
+    /// eje de giro (el del cuerpo) y velocidad angular propia, en radianes por
+    /// unidad de tiempo
+    pub axis: Vec3,
+    pub rotate_speed: f32,
+    /// fracción del cielo cubierta, entre 0 y 1
+    pub coverage: f32,
+    /// opacidad de las nubes más espesas
+    pub opacity: f32,
+    /// lo deprisa que se forman y deshacen (unidades de ruido por unidad de tiempo)
+    pub flow: f32,
+    pub color: Vec3,
+    pub noise: Noise,
+    /// orientación y evolución en el instante actual; las fija `update`
+    frame: [Vec3; 3],
+    phase: f32,
+}
+
+impl Clouds {
+    pub fn new(radius: f32, axis: Vec3, rotate_speed: f32, noise: Noise) -> Self {
+        Clouds {
+            center: Vec3::zero(),
+            radius,
+            axis,
+            rotate_speed,
+            coverage: 0.5,
+            opacity: 0.9,
+            flow: 0.05,
+            color: Vec3::new(1.0, 1.0, 1.0),
+            noise,
+            frame: spin_frame(axis, 0.0),
+            phase: 0.0,
+        }
+    }
+
+    /// Gira y hace evolucionar la capa hasta el instante de simulación `t`.
+    /// Se guarda aquí (y no se toma del rayo) para que las sombras que proyecta
+    /// coincidan siempre con lo que se ve.
+    pub fn update(&mut self, t: f32) {
+        self.frame = spin_frame(self.axis, self.rotate_speed * t);
+        self.phase = self.flow * t;
+    }
+
+    /// Opacidad de la capa en `p` (un punto sobre ella), entre 0 y `opacity`.
+    pub fn alpha_at(&self, p: Vec3) -> f32 {
+        let d = (p - self.center).normalize();
+        let local = Vec3::new(d.dot(self.frame[0]), d.dot(self.frame[1]), d.dot(self.frame[2]));
+
+        // remolinos: fbm deformado por otro fbm, las dos cosas animadas en 4D
+        let n = self.noise.warped4(local * 2.5, self.phase, OCTAVES, 0.8) / FBM_RANGE;
+        // un poco más de nubes en el ecuador y en latitudes medias que en los trópicos
+        let bands = 0.08 * (local.y.asin() * 6.0).cos();
+        let threshold = 1.0 - self.coverage - bands;
+        self.opacity * smoothstep(threshold - 0.06, threshold + 0.1, n)
+    }
+
+    /// Cruces del rayo con la capa dentro de (1e-4, t_max), del más cercano al
+    /// más lejano, con la opacidad de cada uno.
+    fn crossings(&self, ray: &Ray, t_max: f32) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let (near, far) = sphere_span(ray, self.center, self.radius).unwrap_or((-1.0, -1.0));
+        let ray = *ray;
+        [near, far].into_iter()
+            .filter(move |&t| t > 1e-4 && t < t_max)
+            .map(move |t| (t, self.alpha_at(ray.at(t))))
+            .filter(|&(_, alpha)| alpha > 0.0)
+    }
+}
+
+impl Hittable for Clouds {
+    fn hit(&self, ray: &Ray, t_max: f32, _time: f32) -> Option<Hit> {
+        let (t, alpha) = self.crossings(ray, t_max).next()?;
+        let point = ray.at(t);
+        Some(Hit {
+            t,
+            point,
+            // las nubes se iluminan por arriba, se vean desde donde se vean
+            normal: (point - self.center) / self.radius,
+            color: self.color,
+            emissive: Vec3::zero(),
+            night: Vec3::zero(),
+            specular: 0.0,
+            shininess: 1.0,
+            opacity: alpha,
+        })
+    }
+
+    fn distance(&self, ray: &Ray, t_max: f32) -> Option<f32> {
+        self.crossings(ray, t_max).next().map(|(t, _)| t)
+    }
+
+    fn transmittance(&self, ray: &Ray, t_max: f32) -> f32 {
+        // sombra parcial: pasa la luz que dejan los claros en cada cruce
+        self.crossings(ray, t_max).map(|(_, alpha)| 1.0 - alpha).product()
+    }
+}
@@ -0,0 +1,75 @@
+// src/crater.rs
+// Cráteres de impacto sobre celdas de Worley: cuenco con borde elevado, fondo
+// plano en los grandes y eyecta clara alrededor de los más jóvenes.
+use crate::vec3::Vec3;
+use crate::noise::{cell_points, hash1, Noise};
+
+/// Escalas de celda (celdas por radio del cuerpo). Cada octava tiene cuatro
+/// veces más celdas en la superficie, así que los cráteres pequeños abundan
+/// como en una distribución de tamaños N(>D) ~ D^-2.
+const SCALES: [f32; 4] = [3.0, 6.0, 12.0, 24.0];
+
+/// Hasta dónde llega la eyecta, en radios del cráter.
+const EJECTA_REACH: f32 = 2.5;
+
+/// Lo que aportan los cráteres en un punto de la superficie.
+#[derive(Copy, Clone, Debug)]
+pub struct CraterSample {
+    /// desplazamiento radial en radios del cuerpo (negativo dentro del cuenco)
+    pub height: f32,
+    /// factor sobre el color base: <1 en el fondo, >1 en el borde y la eyecta
+    pub albedo: f32,
+}
+
+/// Evalúa los cráteres en `p`, dirección unitaria en el marco del cuerpo.
+/// `density` es la probabilidad de que una celda tenga cráter (0 = ninguno).
+pub fn craters(noise: Noise, p: Vec3, density: f32) -> CraterSample {
+    let mut sample = CraterSample { height: 0.0, albedo: 1.0 };
+    if density <= 0.0 {
+        return sample;
+    }
+
+    for (octave, &scale) in SCALES.iter().enumerate() {
+        let seed = noise.seed.wrapping_add((octave as u32).wrapping_mul(0x9e37_79b9));
+        let q = p * scale;
+        for (center, h) in cell_points(seed, q) {
+            if hash1(h ^ 0x1b873593) >= density {
+                continue;
+            }
+            // radio en celdas, sesgado hacia los pequeños; frescura = cuánto
+            // contraste conserva (los viejos están erosionados)
+            let size = hash1(h ^ 0xcc9e2d51);
+            let radius = 0.12 + 0.28 * size * size;
+            let fresh = 0.3 + 0.7 * hash1(h ^ 0xe6546b64);
+
+            let x = (q - center).length() / radius;
+            if x >= EJECTA_REACH {
+                continue;
+            }
+            let (height, albedo) = profile(x, radius, fresh);
+            sample.height += height / scale;
+            sample.albedo *= albedo;
+        }
+    }
+    sample
+}
+
+/// Perfil de un cráter a `x` radios de su centro: altura (en celdas) y albedo.
+fn profile(x: f32, radius: f32, fresh: f32) -> (f32, f32) {
+    // relación profundidad/diámetro ~0.1; los grandes tienen el fondo plano
+    let depth = 0.2 * radius;
+    let floor = if radius > 0.3 { 0.6 } else { 1.0 };
+    let bowl = if x < 1.0 { (depth * (x * x - 1.0)).max(-depth * floor) } else { 0.0 };
+    let rim_shape = (-((x - 1.0) / 0.18).powi(2)).exp();
+    let rim = depth * 0.35 * rim_shape;
+
+    let mut albedo = 1.0 + 0.2 * fresh * rim_shape;
+    if x < 1.0 {
+        albedo *= 1.0 - 0.25 * fresh * (1.0 - x * x);
+    } else {
+        // eyecta: cae como 1/x³ y se apaga del todo en EJECTA_REACH
+        let fade = 1.0 - (x - 1.0) / (EJECTA_REACH - 1.0);
+        albedo *= 1.0 + 0.35 * fresh * fade * fade / (x * x * x);
+    }
+    (bowl + rim, albedo)
+}
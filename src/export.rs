@@ -0,0 +1,199 @@
+// src/export.rs
+// Exportación de la animación de órbitas: secuencias numeradas, GIF, APNG y
+// vídeo en flujo (Y4M o RGB crudo) hacia un archivo o stdout.
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::scene::Scene;
+use crate::image_out;
+use crate::video::{StreamFormat, VideoWriter};
+
+/// Intervalo de tiempo muestreado a frecuencia fija. El frame `i` siempre cae
+/// en el mismo instante, así que exportar dos veces da el mismo resultado.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameRange {
+    pub start: f32,
+    pub fps: f32,
+    pub frames: usize,
+}
+
+impl FrameRange {
+    pub fn time(&self, i: usize) -> f32 {
+        self.start + i as f32 / self.fps
+    }
+}
+
+/// Renderiza cada frame del intervalo y se lo pasa a `sink` junto con su índice.
+pub fn render_frames(
+    scene: &mut Scene,
+    range: FrameRange,
+    mut sink: impl FnMut(usize, &[u32]) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut buffer = vec![0u32; scene.width * scene.height];
+    for i in 0..range.frames {
+        scene.render(&mut buffer, range.time(i));
+        sink(i, &buffer)?;
+    }
+    Ok(())
+}
+
+/// Exporta según la salida pedida: `.gif`, `.apng`, `.y4m`, `.rgb`, `-` (stdout)
+/// o un patrón numerado como `frames/f_%04d.png` (también vale `.ppm`).
+/// `format` fuerza un flujo de vídeo sea cual sea la extensión. Los errores
+/// llevan delante el archivo al que se refieren.
+pub fn export(
+    scene: &mut Scene,
+    range: FrameRange,
+    output: &Path,
+    format: Option<StreamFormat>,
+) -> io::Result<()> {
+    let name = output.to_string_lossy();
+    if name == "-" {
+        let stdout = io::stdout().lock();
+        return match write_stream(scene, range, stdout, format.unwrap_or(StreamFormat::Y4m)) {
+            // quien lee (ffmpeg, head...) cerró la tubería: no es un fallo nuestro
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            other => other,
+        };
+    }
+    let ext = output.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+    // un patrón numerado sólo si la extensión no pide ya un archivo único
+    let single = matches!(ext.as_deref(), Some("y4m" | "rgb" | "raw" | "gif" | "apng"));
+    if format.is_none() && !single && name.contains('%') {
+        return write_sequence(scene, range, &name);
+    }
+    export_file(scene, range, output, ext.as_deref(), format).map_err(|e| in_file(output, e))
+}
+
+/// Las salidas que son un único archivo.
+fn export_file(
+    scene: &mut Scene,
+    range: FrameRange,
+    output: &Path,
+    ext: Option<&str>,
+    format: Option<StreamFormat>,
+) -> io::Result<()> {
+    if let Some(format) = format {
+        return write_stream(scene, range, File::create(output)?, format);
+    }
+    match ext {
+        Some("y4m") => write_stream(scene, range, File::create(output)?, StreamFormat::Y4m),
+        Some("rgb") | Some("raw") => write_stream(scene, range, File::create(output)?, StreamFormat::Rgb),
+        Some("gif") => write_gif(scene, range, output),
+        Some("apng") => write_apng(scene, range, output),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "usa una salida .gif, .apng, .y4m, .rgb, - o un patrón numerado como frame_%04d.png",
+        )),
+    }
+}
+
+/// Un archivo por frame; crea antes la carpeta para no fallar tras el primer render.
+fn write_sequence(scene: &mut Scene, range: FrameRange, pattern: &str) -> io::Result<()> {
+    let first = sequence_path(pattern, 0)?;
+    if let Some(dir) = first.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| in_file(dir, e))?;
+    }
+    let (w, h) = (scene.width, scene.height);
+    render_frames(scene, range, |i, frame| {
+        let path = sequence_path(pattern, i)?;
+        image_out::save(&path, w, h, frame).map_err(|e| in_file(&path, e))
+    })
+}
+
+/// Antepone `path` al mensaje, conservando el tipo de error.
+fn in_file(path: &Path, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+/// Vuelca los frames como vídeo sin comprimir, sin tocar el disco entre medias.
+fn write_stream(scene: &mut Scene, range: FrameRange, out: impl Write, format: StreamFormat) -> io::Result<()> {
+    let out = BufWriter::with_capacity(1 << 20, out);
+    let mut video = VideoWriter::new(out, format, scene.width, scene.height, range.fps)?;
+    render_frames(scene, range, |_, buffer| video.write_frame(buffer))?;
+    video.finish()
+}
+
+/// Sustituye `%d` o `%0Nd` en el patrón por el número de frame.
+pub fn sequence_path(pattern: &str, index: usize) -> io::Result<PathBuf> {
+    let bad = || io::Error::new(io::ErrorKind::InvalidInput, format!("patrón inválido: {}", pattern));
+    let start = pattern.find('%').ok_or_else(bad)?;
+    let rest = &pattern[start + 1..];
+    let end = rest.find('d').ok_or_else(bad)?;
+    let spec = &rest[..end];
+    let width = if spec.is_empty() {
+        0
+    } else if spec.starts_with('0') {
+        spec.parse::<usize>().map_err(|_| bad())?
+    } else {
+        return Err(bad());
+    };
+    Ok(PathBuf::from(format!("{}{:0w$}{}", &pattern[..start], index, &rest[end + 1..], w = width)))
+}
+
+#[cfg(feature = "gif")]
+fn write_gif(scene: &mut Scene, range: FrameRange, output: &Path) -> io::Result<()> {
+    let (Ok(w), Ok(h)) = (u16::try_from(scene.width), u16::try_from(scene.height)) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "un GIF no admite más de 65535 píxeles por lado"));
+    };
+    let out = BufWriter::new(File::create(output)?);
+    let mut encoder = gif::Encoder::new(out, w, h, &[]).map_err(io::Error::other)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+    // el GIF mide los retardos en centésimas de segundo
+    let delay = (100.0 / range.fps).round().max(1.0) as u16;
+
+    render_frames(scene, range, |_, buffer| {
+        let rgb = image_out::to_rgb8(buffer);
+        let mut frame = gif::Frame::from_rgb_speed(w, h, &rgb, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)
+    })
+}
+
+#[cfg(not(feature = "gif"))]
+fn write_gif(_: &mut Scene, _: FrameRange, _: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "compilado sin la feature `gif`"))
+}
+
+#[cfg(feature = "png")]
+fn write_apng(scene: &mut Scene, range: FrameRange, output: &Path) -> io::Result<()> {
+    let out = BufWriter::new(File::create(output)?);
+    let mut encoder = png::Encoder::new(out, scene.width as u32, scene.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(range.frames as u32, 0).map_err(io::Error::other)?;
+    // retardo = 100 / (fps * 100) segundos, con enteros de 16 bits
+    encoder.set_frame_delay(100, (range.fps * 100.0).round().clamp(1.0, u16::MAX as f32) as u16)
+        .map_err(io::Error::other)?;
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+
+    render_frames(scene, range, |_, buffer| {
+        writer.write_image_data(&image_out::to_rgb8(buffer)).map_err(io::Error::other)
+    })?;
+    writer.finish().map_err(io::Error::other)
+}
+
+#[cfg(not(feature = "png"))]
+fn write_apng(_: &mut Scene, _: FrameRange, _: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "compilado sin la feature `png`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_path_expands_frame_number() {
+        assert_eq!(sequence_path("frames/f_%04d.png", 7).unwrap(), PathBuf::from("frames/f_0007.png"));
+        assert_eq!(sequence_path("f%d.ppm", 12).unwrap(), PathBuf::from("f12.ppm"));
+        assert_eq!(sequence_path("f_%02d.png", 123).unwrap(), PathBuf::from("f_123.png"));
+    }
+
+    #[test]
+    fn sequence_path_rejects_bad_patterns() {
+        for pattern in ["frames.png", "f_%5d.png", "f_%s.png", "f_%0xd.png"] {
+            assert!(sequence_path(pattern, 0).is_err(), "{} debería fallar", pattern);
+        }
+    }
+}
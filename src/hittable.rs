@@ -0,0 +1,48 @@
+// src/hittable.rs
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::noise::smoothstep;
+
+/// Resultado de intersectar un rayo con un objeto, con su material ya evaluado.
+pub struct Hit {
+    pub t: f32,
+    pub point: Vec3,
+    /// normal unitaria; las superficies sin interior (anillos) la orientan hacia el rayo
+    pub normal: Vec3,
+    pub color: Vec3,
+    pub emissive: Vec3,
+    /// luz propia que sólo se ve a oscuras (ciudades, lava, auroras)
+    pub night: Vec3,
+    pub specular: f32,
+    pub shininess: f32,
+    /// 1 = opaco; con menos, lo que hay detrás se ve a través
+    pub opacity: f32,
+}
+
+impl Hit {
+    /// `night` atenuada según `light`, la luz directa que recibe el punto (su
+    /// componente mayor): entera en el lado nocturno y nada a plena luz.
+    pub fn night_glow(&self, light: f32) -> Vec3 {
+        self.night * (1.0 - smoothstep(0.0, 0.2, light))
+    }
+}
+
+/// Cualquier cosa que se pueda intersectar: esferas, anillos, elipsoides, mallas, SDF...
+///
+/// La escena sólo conoce este trait, así que todos los objetos comparten la
+/// búsqueda del impacto más cercano y los rayos de sombra.
+pub trait Hittable: Send + Sync {
+    /// Impacto más cercano con `t < t_max` (cada objeto descarta su propio epsilon).
+    fn hit(&self, ray: &Ray, t_max: f32, time: f32) -> Option<Hit>;
+
+    /// Sólo la distancia del impacto; para rayos de sombra no hace falta sombrear.
+    fn distance(&self, ray: &Ray, t_max: f32) -> Option<f32> {
+        self.hit(ray, t_max, 0.0).map(|h| h.t)
+    }
+
+    /// Fracción de luz que deja pasar entre el origen del rayo y `t_max`
+    /// (0 = sombra completa). Los objetos opacos sólo devuelven 0 o 1.
+    fn transmittance(&self, ray: &Ray, t_max: f32) -> f32 {
+        if self.distance(ray, t_max).is_some() { 0.0 } else { 1.0 }
+    }
+}
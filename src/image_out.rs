@@ -0,0 +1,50 @@
+// src/image_out.rs
+// Escritura de frames (buffers 0RGB de minifb) a archivos de imagen.
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Convierte un buffer 0x00RRGGBB a bytes RGB intercalados.
+pub fn to_rgb8(buffer: &[u32]) -> Vec<u8> {
+    buffer.iter()
+        .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8])
+        .collect()
+}
+
+/// PPM binario (P6): sin dependencias, lo abre casi cualquier visor.
+pub fn write_ppm(path: &Path, width: usize, height: usize, buffer: &[u32]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    out.write_all(&to_rgb8(buffer))?;
+    out.flush()
+}
+
+#[cfg(feature = "png")]
+pub fn write_png(path: &Path, width: usize, height: usize, buffer: &[u32]) -> io::Result<()> {
+    let out = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&to_rgb8(buffer)).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Elige el formato por la extensión (`.png` o `.ppm`).
+pub fn save(path: &Path, width: usize, height: usize, buffer: &[u32]) -> io::Result<()> {
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("ppm") => write_ppm(path, width, height, buffer),
+        #[cfg(feature = "png")]
+        Some("png") => write_png(path, width, height, buffer),
+        #[cfg(not(feature = "png"))]
+        Some("png") => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "compilado sin la feature `png`; usa una salida .ppm",
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("formato de imagen desconocido: {} (usa .png o .ppm)", path.display()),
+        )),
+    }
+}
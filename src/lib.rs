@@ -0,0 +1,31 @@
+// src/lib.rs
+//! Espacio: trazador de rayos por CPU de un sistema solar animado.
+//!
+//! La biblioteca no abre ventanas: carga una escena (`Scene::from_file`,
+//! `Scene::from_toml` o `Scene::new` para la de por defecto), la coloca en un
+//! instante con `Scene::update` y la dibuja en un buffer 0x00RRGGBB con
+//! `Scene::render`; `renderer::render_planet` dibuja un cuerpo suelto de frente
+//! para miniaturas. El visor interactivo vive en el binario, detrás de la
+//! feature `viewer`.
+pub mod vec3;
+pub mod ray;
+pub mod color;
+pub mod noise;
+pub mod crater;
+pub mod hittable;
+pub mod sphere;
+pub mod orbit;
+pub mod ring;
+pub mod atmosphere;
+pub mod clouds;
+pub mod body;
+pub mod physics;
+pub mod scene;
+pub mod scene_file;
+pub mod image_out;
+pub mod export;
+pub mod video;
+pub mod renderer;
+
+pub use scene::Scene;
+pub use scene_file::SceneError;
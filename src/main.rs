@@ -1,17 +1,118 @@
-mod vec3;
-mod ray;
-mod color;
-mod noise;
-mod sphere;
-mod scene;
+mod cli;
 
-use minifb::{WindowOptions, Window, Key};
-use crate::scene::Scene;
-use std::time::Instant;
+use espacio::{export, image_out, renderer, Scene};
+use crate::cli::{Command, Options};
+use std::path::Path;
 
 fn main() {
-    let mut width = 1024;
-    let mut height = 640;
+    let command = match cli::parse(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::View(opts) => run_viewer(&opts),
+        Command::Render { opts, output, time } => render_still(&opts, &output, time),
+        Command::Export { opts, output, range, format } => {
+            let mut scene = load_scene(&opts);
+            if let Err(e) = export::export(&mut scene, range, &output, format) {
+                fail(e);
+            }
+        }
+        Command::Preview { opts, output, time, body, size } => {
+            render_previews(&opts, &output, time, body.as_deref(), size)
+        }
+    }
+}
+
+/// Carga la escena pedida (o la de por defecto) y aplica los ajustes de la línea de comandos.
+fn load_scene(opts: &Options) -> Scene {
+    let mut scene = match &opts.scene {
+        Some(path) => match Scene::from_file(path, opts.width, opts.height) {
+            Ok(scene) => scene,
+            Err(e) => fail(e),
+        },
+        None => Scene::new(opts.width, opts.height),
+    };
+
+    if let Some(pos) = opts.camera {
+        // sin --look-at se conserva la dirección de la escena
+        scene.camera_target = pos + (scene.camera_target - scene.camera_pos);
+        scene.camera_pos = pos;
+    }
+    if let Some(target) = opts.look_at {
+        scene.camera_target = target;
+    }
+    if (scene.camera_target - scene.camera_pos).length() == 0.0 {
+        fail("--look-at no puede coincidir con la posición de la cámara");
+    }
+    if let Some(fov) = opts.fov {
+        if !(fov > 0.0 && fov < std::f32::consts::PI) {
+            fail("--fov debe estar entre 0 y pi");
+        }
+        scene.fov = fov;
+    }
+    if let Some(n) = opts.shadow_samples {
+        scene.shadow_samples = n;
+    }
+    scene
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", e);
+    std::process::exit(1);
+}
+
+fn render_still(opts: &Options, output: &Path, time: f32) {
+    let mut scene = load_scene(opts);
+    let mut buffer = vec![0u32; opts.width * opts.height];
+    scene.render(&mut buffer, time);
+    if let Err(e) = image_out::save(output, opts.width, opts.height, &buffer) {
+        fail(format!("{}: {}", output.display(), e));
+    }
+}
+
+/// Una miniatura por cuerpo en `dir`, con el nombre del cuerpo en minúsculas.
+fn render_previews(opts: &Options, dir: &Path, time: f32, only: Option<&str>, size: usize) {
+    let mut scene = load_scene(opts);
+    if let Some(name) = only {
+        if scene.find(name).is_none() {
+            fail(format!("la escena no tiene ningún cuerpo llamado \"{}\"", name));
+        }
+    }
+    scene.update(time * scene.time_scale);
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        fail(format!("{}: {}", dir.display(), e));
+    }
+
+    let ext = if cfg!(feature = "png") { "png" } else { "ppm" };
+    for body in scene.bodies.iter().filter(|b| only.is_none_or(|n| b.name == n)) {
+        let file = body.name.to_lowercase().replace(|c: char| !c.is_alphanumeric(), "_");
+        let path = dir.join(file).with_extension(ext);
+        let buffer = renderer::render_planet(body, size, time);
+        if let Err(e) = image_out::save(&path, size, size, &buffer) {
+            fail(format!("{}: {}", path.display(), e));
+        }
+    }
+}
+
+#[cfg(not(feature = "viewer"))]
+fn run_viewer(_: &Options) {
+    fail("compilado sin la feature `viewer`; usa `render` o `export`");
+}
+
+#[cfg(feature = "viewer")]
+fn run_viewer(opts: &Options) {
+    use minifb::{WindowOptions, Window, Key};
+    use std::time::Instant;
+
+    let mut width = opts.width;
+    let mut height = opts.height;
+    let mut scene = load_scene(opts);
 
     let mut window = Window::new(
         "Planet Shaders - CPU Renderer",
@@ -20,21 +121,20 @@ fn main() {
         WindowOptions::default()
     ).unwrap();
 
-    window.limit_update_rate(Some(std::time::Duration::from_millis(16)));
+    window.set_target_fps(60);
 
     let mut buffer = vec![0u32; width * height];
-    let mut scene = Scene::new(width, height);
 
     let start = Instant::now();
 
     while window.is_open() && !window.is_key_down(Key::Escape) {
         let t = (Instant::now() - start).as_secs_f32();
 
-        // Actualización por si resize 
+        // Actualización por si resize
         let (w, h) = window.get_size();
-        if w as usize != width || h as usize != height {
-            width = w as usize;
-            height = h as usize;
+        if w != width || h != height {
+            width = w;
+            height = h;
             buffer = vec![0u32; width * height];
             scene.width = width;
             scene.height = height;
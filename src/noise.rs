@@ -1,31 +1,381 @@
 use crate::vec3::Vec3;
+use serde::Deserialize;
 
-pub fn hash1(mut n: u32) -> f32 {
+/// Mezcla de bits entera (hash de Wang); base de todo el ruido.
+fn mix(mut n: u32) -> u32 {
     n = (n ^ 61).wrapping_add(n << 3);
     n = n ^ (n >> 4);
     n = n.wrapping_mul(0x27d4eb2d);
-    n = n ^ (n >> 15);
-    (n as f32) / (u32::MAX as f32)
+    n ^ (n >> 15)
 }
 
-pub fn noise3(p: Vec3) -> f32 {
+pub fn hash1(n: u32) -> f32 {
+    (mix(n) as f32) / (u32::MAX as f32)
+}
+
+/// Paso suave de 0 a 1 mientras `x` va de `edge0` a `edge1` (Hermite cúbico).
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+const LATTICE_PRIMES: [u32; 4] = [73856093, 19349663, 83492791, 2654435761];
+
+/// Hash entero de una celda de la red; base del ruido de gradiente y de Worley.
+pub fn hash_cell<const N: usize>(seed: u32, cell: [i32; N]) -> u32 {
+    cell.iter().zip(LATTICE_PRIMES).fold(mix(seed), |h, (&c, prime)| mix(h ^ (c as u32).wrapping_mul(prime)))
+}
+
+/// Ruido de valor sin interpolar: salta de golpe entre celdas. Se conserva para
+/// comparar con el ruido de gradiente (`Basis::Hash`); con semilla 0 da el
+/// patrón de siempre.
+pub fn noise3(seed: u32, p: Vec3) -> f32 {
     // coarse hashing of coordinates
     let xi = (p.x * 12.9898) as i32;
     let yi = (p.y * 78.233) as i32;
     let zi = (p.z * 37.719) as i32;
     let mut n = xi as u32;
     n = n.wrapping_mul(73856093) ^ ((yi as u32).wrapping_mul(19349663)) ^ ((zi as u32).wrapping_mul(83492791));
-    hash1(n)
+    hash1(n ^ seed.wrapping_mul(0x9e3779b9))
+}
+
+/// Semilla reproducible para un cuerpo sin semilla propia: depende sólo de la
+/// semilla de la escena y del nombre (FNV-1a), no del orden en el archivo.
+pub fn derive_seed(scene_seed: u32, name: &str) -> u32 {
+    let h = name.bytes().fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+    mix(h ^ mix(scene_seed))
+}
+
+/// Ruido base que usan los shaders.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Basis {
+    /// `noise3`: bloques, sin derivadas
+    Hash,
+    /// Perlin clásico con interpolación quíntica
+    Perlin,
+    /// simplex: más barato e isótropo que Perlin
+    #[default]
+    Simplex,
+}
+
+/// Un ruido concreto: la base más la semilla que lo hace único. Dos cuerpos con
+/// la misma semilla y la misma base tienen exactamente la misma superficie.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Noise {
+    pub basis: Basis,
+    pub seed: u32,
+}
+
+impl Noise {
+    pub fn new(basis: Basis, seed: u32) -> Self {
+        Noise { basis, seed }
+    }
+
+    /// Valor en [0, 1] aproximadamente, como el viejo `noise3`.
+    pub fn sample(self, p: Vec3) -> f32 {
+        match self.basis {
+            Basis::Hash => noise3(self.seed, p),
+            Basis::Perlin => 0.5 + 0.5 * perlin3(self.seed, p),
+            Basis::Simplex => 0.5 + 0.5 * simplex3(self.seed, p),
+        }
+    }
+
+    /// `sample` junto con su gradiente respecto a `p`.
+    pub fn sample_d(self, p: Vec3) -> (f32, Vec3) {
+        let (n, d) = match self.basis {
+            Basis::Hash => return (noise3(self.seed, p), Vec3::zero()),
+            Basis::Perlin => perlin3_d(self.seed, p),
+            Basis::Simplex => simplex3_d(self.seed, p),
+        };
+        (0.5 + 0.5 * n, d * 0.5)
+    }
+
+    /// Como `sample` pero centrado en 0: [-1, 1] aproximadamente.
+    pub fn signed(self, p: Vec3) -> f32 {
+        2.0 * self.sample(p) - 1.0
+    }
+
+    pub fn fbm(self, p: Vec3, octaves: usize) -> f32 {
+        octave_sum(octaves, |freq| self.sample(p * freq)).0
+    }
+
+    /// Multifractal con crestas (Musgrave): `1 - |n|` al cuadrado da crestas
+    /// afiladas, y cada octava pesa según la anterior, así que el detalle fino
+    /// se acumula sobre las cumbres y los valles quedan lisos. En [0, 1].
+    pub fn ridged(self, p: Vec3, octaves: usize) -> f32 {
+        let mut weight = 1.0;
+        let (sum, norm) = octave_sum(octaves, |freq| {
+            let ridge = 1.0 - self.signed(p * freq).abs();
+            let signal = ridge * ridge * weight;
+            weight = (signal * 2.0).clamp(0.0, 1.0);
+            signal
+        });
+        sum / norm
+    }
+
+    /// `sample` con una cuarta coordenada, normalmente el tiempo: el patrón
+    /// evoluciona en su sitio en vez de deslizarse. `Basis::Hash` no tiene 4D
+    /// y se limita a desplazar `p`, como hacían los shaders antes.
+    pub fn sample4(self, p: Vec3, w: f32) -> f32 {
+        match self.basis {
+            Basis::Hash => noise3(self.seed, p + Vec3::new(w, 0.0, 0.0)),
+            Basis::Perlin => 0.5 + 0.5 * perlin4(self.seed, p, w),
+            Basis::Simplex => 0.5 + 0.5 * simplex4(self.seed, p, w),
+        }
+    }
+
+    /// `fbm` animado; las octavas finas también cambian más deprisa.
+    pub fn fbm4(self, p: Vec3, w: f32, octaves: usize) -> f32 {
+        octave_sum(octaves, |freq| self.sample4(p * freq, w * freq)).0
+    }
+
+    /// Turbulencia animada: suma de `|n|`, con pliegues donde el ruido cruza 0.
+    /// En [0, 1].
+    pub fn turbulence4(self, p: Vec3, w: f32, octaves: usize) -> f32 {
+        let (sum, norm) = octave_sum(octaves, |freq| (2.0 * self.sample4(p * freq, w * freq) - 1.0).abs());
+        sum / norm
+    }
+
+    /// fbm animado con el dominio deformado por otro fbm: `fbm4(p + strength * q(p))`.
+    /// Estira y retuerce el patrón como un fluido, útil para remolinos; tanto la
+    /// deformación como el patrón evolucionan con `w`.
+    pub fn warped4(self, p: Vec3, w: f32, octaves: usize, strength: f32) -> f32 {
+        // desplazamientos arbitrarios para que las tres componentes no se parezcan
+        let q = Vec3::new(
+            self.fbm4(p, w, octaves),
+            self.fbm4(p + Vec3::new(5.2, 1.3, 2.8), w, octaves),
+            self.fbm4(p + Vec3::new(1.7, 9.2, 4.1), w, octaves),
+        ) - Vec3::new(1.0, 1.0, 1.0);
+        self.fbm4(p + q * strength, w, octaves)
+    }
+
+    pub fn worley(self, p: Vec3) -> Worley {
+        worley3(self.seed, p)
+    }
 }
 
-pub fn fbm(p: Vec3, octaves: usize) -> f32 {
+/// Bucle de octavas común a todos los fractales: suma `layer(freq)` duplicando
+/// la frecuencia y dividiendo la amplitud a la mitad en cada octava. Devuelve la
+/// suma y la suma de amplitudes, para los que normalizan a [0, 1].
+fn octave_sum(octaves: usize, mut layer: impl FnMut(f32) -> f32) -> (f32, f32) {
     let mut sum = 0.0;
+    let mut norm = 0.0;
     let mut amp = 1.0;
     let mut freq = 1.0;
     for _ in 0..octaves {
-        sum += noise3(p * freq) * amp;
+        sum += layer(freq) * amp;
+        norm += amp;
         freq *= 2.0;
         amp *= 0.5;
     }
-    sum
+    (sum, norm)
+}
+
+// --- ruido celular (Worley) --------------------------------------------------
+
+/// Resultado de Worley: distancias al punto más cercano y al segundo, y el hash
+/// de la celda del más cercano (para dar a cada celda propiedades propias).
+#[derive(Copy, Clone, Debug)]
+pub struct Worley {
+    pub f1: f32,
+    pub f2: f32,
+    pub id: u32,
+}
+
+/// Un punto por celda de la red, desplazado al azar dentro de ella; devuelve los
+/// de las 27 celdas que rodean a `p` junto con el hash de su celda.
+pub fn cell_points(seed: u32, p: Vec3) -> impl Iterator<Item = (Vec3, u32)> {
+    let base = [p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32];
+    (0..27).map(move |k| {
+        let cell = [base[0] + k % 3 - 1, base[1] + (k / 3) % 3 - 1, base[2] + k / 9 - 1];
+        let h = hash_cell(seed, cell);
+        let jitter = Vec3::new(hash1(h), hash1(h ^ 0x68e31da4), hash1(h ^ 0xb5297a4d));
+        (Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32) + jitter, h)
+    })
+}
+
+pub fn worley3(seed: u32, p: Vec3) -> Worley {
+    let mut w = Worley { f1: f32::INFINITY, f2: f32::INFINITY, id: 0 };
+    for (q, h) in cell_points(seed, p) {
+        let d = (q - p).length();
+        if d < w.f1 {
+            w.f2 = w.f1;
+            w.f1 = d;
+            w.id = h;
+        } else if d < w.f2 {
+            w.f2 = d;
+        }
+    }
+    w
+}
+
+// --- ruido de gradiente -------------------------------------------------------
+//
+// Las dos variantes son genéricas en la dimensión: 3D para superficies y 4D para
+// animarlas en el tiempo sin que el patrón se deslice. Todas devuelven valores
+// en [-1, 1] aproximadamente y el gradiente analítico; la semilla cambia los
+// gradientes de la red, así que cada semilla da un patrón independiente.
+
+pub fn perlin3(seed: u32, p: Vec3) -> f32 {
+    perlin(seed, [p.x, p.y, p.z]).0
+}
+
+pub fn perlin3_d(seed: u32, p: Vec3) -> (f32, Vec3) {
+    let (n, d) = perlin(seed, [p.x, p.y, p.z]);
+    (n, Vec3::new(d[0], d[1], d[2]))
+}
+
+pub fn perlin4(seed: u32, p: Vec3, w: f32) -> f32 {
+    perlin(seed, [p.x, p.y, p.z, w]).0
+}
+
+pub fn simplex3(seed: u32, p: Vec3) -> f32 {
+    simplex(seed, [p.x, p.y, p.z], 75.0).0
+}
+
+pub fn simplex3_d(seed: u32, p: Vec3) -> (f32, Vec3) {
+    let (n, d) = simplex(seed, [p.x, p.y, p.z], 75.0);
+    (n, Vec3::new(d[0], d[1], d[2]))
+}
+
+pub fn simplex4(seed: u32, p: Vec3, w: f32) -> f32 {
+    simplex(seed, [p.x, p.y, p.z, w], 62.0).0
+}
+
+/// Gradiente pseudoaleatorio de la celda: los puntos medios de las aristas del
+/// hipercubo (12 en 3D, 32 en 4D), una componente a 0 y el resto ±1.
+fn gradient<const N: usize>(h: u32) -> [f32; N] {
+    let zero = (h % N as u32) as usize;
+    let mut g = [0.0; N];
+    for (i, gi) in g.iter_mut().enumerate() {
+        if i != zero {
+            *gi = if (h >> (8 + i)) & 1 == 0 { 1.0 } else { -1.0 };
+        }
+    }
+    g
+}
+
+fn dot<const N: usize>(a: [f32; N], b: [f32; N]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Perlin en N dimensiones: mezcla los 2^N productos gradiente·offset de las
+/// esquinas con pesos quínticos; la derivada sale de derivar esa mezcla.
+fn perlin<const N: usize>(seed: u32, p: [f32; N]) -> (f32, [f32; N]) {
+    let cell = p.map(|x| x.floor());
+    let f: [f32; N] = std::array::from_fn(|i| p[i] - cell[i]);
+    // fade quíntico 6t^5 - 15t^4 + 10t^3 y su derivada
+    let u = f.map(|t| t * t * t * (t * (t * 6.0 - 15.0) + 10.0));
+    let du = f.map(|t| 30.0 * t * t * (t * (t - 2.0) + 1.0));
+
+    let mut value = 0.0;
+    let mut deriv = [0.0; N];
+    for corner in 0..1usize << N {
+        let bit = |i: usize| (corner >> i) & 1;
+        let ci: [i32; N] = std::array::from_fn(|i| cell[i] as i32 + bit(i) as i32);
+        let g = gradient::<N>(hash_cell(seed, ci));
+        let offset: [f32; N] = std::array::from_fn(|i| f[i] - bit(i) as f32);
+        let v = dot(g, offset);
+
+        let w: [f32; N] = std::array::from_fn(|i| if bit(i) == 1 { u[i] } else { 1.0 - u[i] });
+        let weight: f32 = w.iter().product();
+        value += weight * v;
+        for i in 0..N {
+            // d(peso)/dx_i: se sustituye el factor i por su derivada
+            let others: f32 = (0..N).filter(|&j| j != i).map(|j| w[j]).product();
+            let dw = if bit(i) == 1 { du[i] } else { -du[i] };
+            deriv[i] += weight * g[i] + v * dw * others;
+        }
+    }
+    (value, deriv)
+}
+
+/// Simplex en N dimensiones (3 o 4): suma de núcleos radiales (0.5 - r²)^4
+/// centrados en las N+1 esquinas del símplice que contiene a `p`. Con radio² 0.5
+/// ningún núcleo sale del símplice, así que no hay saltos en los bordes.
+fn simplex<const N: usize>(seed: u32, p: [f32; N], scale: f32) -> (f32, [f32; N]) {
+    let n = N as f32;
+    let skew = ((n + 1.0).sqrt() - 1.0) / n;
+    let unskew = (1.0 - 1.0 / (n + 1.0).sqrt()) / n;
+
+    let s = p.iter().sum::<f32>() * skew;
+    let cell: [i32; N] = p.map(|x| (x + s).floor() as i32);
+    let t = cell.iter().map(|&c| c as f32).sum::<f32>() * unskew;
+    let x0: [f32; N] = std::array::from_fn(|i| p[i] - (cell[i] as f32 - t));
+
+    // se recorre el símplice sumando 1 a los ejes de mayor a menor offset
+    let mut order: [usize; N] = std::array::from_fn(|i| i);
+    order.sort_by(|&a, &b| x0[b].total_cmp(&x0[a]));
+
+    let mut value = 0.0;
+    let mut deriv = [0.0; N];
+    let mut step = [0i32; N];
+    for k in 0..=N {
+        if k > 0 {
+            step[order[k - 1]] = 1;
+        }
+        let x: [f32; N] = std::array::from_fn(|i| x0[i] - step[i] as f32 + k as f32 * unskew);
+        let falloff = 0.5 - dot(x, x);
+        if falloff <= 0.0 { continue; }
+
+        let ci: [i32; N] = std::array::from_fn(|i| cell[i] + step[i]);
+        let g = gradient::<N>(hash_cell(seed, ci));
+        let gx = dot(g, x);
+        let f2 = falloff * falloff;
+        let f4 = f2 * f2;
+        value += f4 * gx;
+        for i in 0..N {
+            deriv[i] += f4 * g[i] - 8.0 * f2 * falloff * gx * x[i];
+        }
+    }
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Puntos arbitrarios repartidos por varias celdas de la red.
+    fn points() -> impl Iterator<Item = [f32; 4]> {
+        (0..64).map(|i| std::array::from_fn(|k| 7.0 * hash1(4 * i + k as u32) - 3.5))
+    }
+
+    /// Compara la derivada analítica de `f` con diferencias centrales.
+    fn check_gradient<const N: usize>(name: &str, f: impl Fn([f32; N]) -> (f32, [f32; N])) {
+        const H: f32 = 1e-3;
+        for q in points() {
+            let p: [f32; N] = std::array::from_fn(|i| q[i]);
+            let (_, grad) = f(p);
+            for i in 0..N {
+                let mut hi = p;
+                let mut lo = p;
+                hi[i] += H;
+                lo[i] -= H;
+                let numeric = (f(hi).0 - f(lo).0) / (2.0 * H);
+                let err = (numeric - grad[i]).abs();
+                assert!(err < 0.02 * grad[i].abs().max(1.0), "{} en {:?}, eje {}: analítica {}, numérica {}", name, p, i, grad[i], numeric);
+            }
+        }
+    }
+
+    #[test]
+    fn gradients_match_finite_differences() {
+        check_gradient("perlin 3D", |p: [f32; 3]| perlin(7, p));
+        check_gradient("perlin 4D", |p: [f32; 4]| perlin(7, p));
+        check_gradient("simplex 3D", |p: [f32; 3]| simplex(7, p, 75.0));
+        check_gradient("simplex 4D", |p: [f32; 4]| simplex(7, p, 62.0));
+    }
+
+    #[test]
+    fn sample_d_matches_sample() {
+        for basis in [Basis::Perlin, Basis::Simplex] {
+            let noise = Noise::new(basis, 3);
+            check_gradient("sample_d", |p: [f32; 3]| {
+                let (n, d) = noise.sample_d(Vec3::new(p[0], p[1], p[2]));
+                assert_eq!(n, noise.sample(Vec3::new(p[0], p[1], p[2])));
+                (n, [d.x, d.y, d.z])
+            });
+        }
+    }
 }
@@ -0,0 +1,123 @@
+// src/orbit.rs
+use std::f32::consts::TAU;
+
+use crate::vec3::Vec3;
+
+/// Órbita kepleriana alrededor del padre del cuerpo.
+///
+/// Los ángulos están en radianes y se miden respecto al plano XZ de la escena
+/// (Y hacia arriba); el periodo está en unidades de tiempo de la escena.
+#[derive(Copy, Clone, Debug)]
+pub struct Orbit {
+    pub semi_major_axis: f32,
+    pub eccentricity: f32,
+    pub inclination: f32,
+    /// longitud del nodo ascendente (Ω)
+    pub ascending_node: f32,
+    /// argumento del periapsis (ω)
+    pub periapsis: f32,
+    /// anomalía media en t = 0
+    pub mean_anomaly: f32,
+    pub period: f32,
+}
+
+impl Orbit {
+    /// Anomalía media en el instante `t`, reducida a [0, 2pi).
+    pub fn mean_anomaly_at(&self, t: f32) -> f32 {
+        (self.mean_anomaly + TAU * t / self.period).rem_euclid(TAU)
+    }
+
+    /// Resuelve la ecuación de Kepler `M = E - e sin E` por Newton.
+    pub fn eccentric_anomaly(&self, mean_anomaly: f32) -> f32 {
+        let e = self.eccentricity;
+        let m = mean_anomaly;
+        // con excentricidades altas arrancar desde pi converge mejor
+        let mut ea = if e < 0.8 { m + e * m.sin() } else { std::f32::consts::PI };
+        for _ in 0..12 {
+            // restar primero M deja menos error de redondeo cuando E y M son grandes
+            let f = (ea - m) - e * ea.sin();
+            let d = f / (1.0 - e * ea.cos());
+            ea -= d;
+            if d.abs() < 1e-6 { break; }
+        }
+        ea
+    }
+
+    /// Desplazamiento respecto al padre en el instante `t`.
+    pub fn offset_at(&self, t: f32) -> Vec3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+        let ea = self.eccentric_anomaly(self.mean_anomaly_at(t));
+
+        // posición en el plano de la órbita, con el periapsis sobre +x
+        let xp = a * (ea.cos() - e);
+        let yp = a * (1.0 - e * e).sqrt() * ea.sin();
+
+        // rotar por ω, luego inclinar por i y girar por Ω
+        let (sw, cw) = self.periapsis.sin_cos();
+        let (si, ci) = self.inclination.sin_cos();
+        let (sn, cn) = self.ascending_node.sin_cos();
+        let x1 = xp * cw - yp * sw;
+        let y1 = xp * sw + yp * cw;
+        let y2 = y1 * ci;
+        let z2 = y1 * si;
+        let x3 = x1 * cn - y2 * sn;
+        let y3 = x1 * sn + y2 * cn;
+
+        // el plano de referencia es XZ, con el polo norte de la eclíptica en +Y
+        Vec3::new(x3, z2, y3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(eccentricity: f32) -> Orbit {
+        Orbit {
+            semi_major_axis: 10.0,
+            eccentricity,
+            inclination: 0.4,
+            ascending_node: 1.1,
+            periapsis: 2.3,
+            mean_anomaly: 0.0,
+            period: 20.0,
+        }
+    }
+
+    #[test]
+    fn kepler_residual_is_small_up_to_high_eccentricity() {
+        for e in [0.0, 0.1, 0.5, 0.8, 0.9, 0.95, 0.99] {
+            let o = orbit(e);
+            for i in 0..1000 {
+                let m = TAU * i as f32 / 1000.0;
+                let ea = o.eccentric_anomaly(m) as f64;
+                let residual = ea - e as f64 * ea.sin() - m as f64;
+                assert!(residual.abs() < 5e-7, "e = {}, M = {}: residuo {}", e, m, residual);
+            }
+        }
+    }
+
+    #[test]
+    fn periapsis_and_apoapsis_distances() {
+        for e in [0.0, 0.3, 0.7, 0.99] {
+            let o = orbit(e);
+            let a = o.semi_major_axis;
+            let peri = o.offset_at(0.0).length();
+            let apo = o.offset_at(0.5 * o.period).length();
+            assert!((peri - a * (1.0 - e)).abs() < 1e-4 * a, "e = {}: periapsis {}", e, peri);
+            assert!((apo - a * (1.0 + e)).abs() < 1e-4 * a, "e = {}: apoapsis {}", e, apo);
+        }
+    }
+
+    #[test]
+    fn returns_to_start_after_one_period() {
+        for e in [0.0, 0.5, 0.9] {
+            let o = Orbit { mean_anomaly: 0.7, ..orbit(e) };
+            for t in [0.0, 3.7, 11.2] {
+                let d = o.offset_at(t + o.period) - o.offset_at(t);
+                assert!(d.length() < 1e-3, "e = {}, t = {}: se desvía {}", e, t, d.length());
+            }
+        }
+    }
+}
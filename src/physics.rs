@@ -0,0 +1,169 @@
+// src/physics.rs
+// Modo de simulación gravitatoria de N cuerpos, alternativo a las órbitas keplerianas.
+use serde::Deserialize;
+
+use crate::vec3::Vec3;
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrator {
+    /// kick-drift-kick (Verlet de velocidades), simpléctico
+    Leapfrog,
+    /// Runge-Kutta clásico de orden 4, más preciso por paso pero no simpléctico
+    Rk4,
+}
+
+/// Estado de la simulación. Avanza en pasos fijos de `dt` sin importar cuántas
+/// veces por segundo se llame a `advance_to`, así que es determinista.
+pub struct Simulation {
+    pub gravity: f32,
+    pub dt: f32,
+    pub softening: f32,
+    pub integrator: Integrator,
+    pub mass: Vec<f32>,
+    pub pos: Vec<Vec3>,
+    pub vel: Vec<Vec3>,
+    steps: u64,
+    initial: (Vec<Vec3>, Vec<Vec3>),
+}
+
+impl Simulation {
+    pub fn new(
+        gravity: f32,
+        dt: f32,
+        softening: f32,
+        integrator: Integrator,
+        mass: Vec<f32>,
+        pos: Vec<Vec3>,
+        mut vel: Vec<Vec3>,
+    ) -> Self {
+        // quitar la deriva del centro de masas para que el sistema no se salga de cuadro
+        let total: f32 = mass.iter().sum();
+        if total > 0.0 {
+            let p = mass.iter().zip(&vel).fold(Vec3::zero(), |acc, (&m, &v)| acc + v * m);
+            let v_cm = p / total;
+            for v in vel.iter_mut() {
+                *v = *v - v_cm;
+            }
+        }
+        Simulation {
+            gravity,
+            dt,
+            softening,
+            integrator,
+            mass,
+            initial: (pos.clone(), vel.clone()),
+            pos,
+            vel,
+            steps: 0,
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        // contar pasos en vez de sumar dt evita acumular error de redondeo
+        self.steps as f32 * self.dt
+    }
+
+    /// Vuelve al estado inicial (t = 0).
+    pub fn reset(&mut self) {
+        self.pos = self.initial.0.clone();
+        self.vel = self.initial.1.clone();
+        self.steps = 0;
+    }
+
+    /// Avanza con pasos fijos hasta el último paso que no supera `t`.
+    /// Si `t` es anterior al tiempo actual se reinicia desde t = 0.
+    pub fn advance_to(&mut self, t: f32) {
+        if t < self.time() {
+            self.reset();
+        }
+        let target = (t.max(0.0) / self.dt).floor() as u64;
+        while self.steps < target {
+            self.step();
+            self.steps += 1;
+        }
+    }
+
+    pub fn step(&mut self) {
+        match self.integrator {
+            Integrator::Leapfrog => self.step_leapfrog(),
+            Integrator::Rk4 => self.step_rk4(),
+        }
+    }
+
+    fn accelerations(&self, pos: &[Vec3]) -> Vec<Vec3> {
+        let eps2 = self.softening * self.softening;
+        let mut acc = vec![Vec3::zero(); pos.len()];
+        for i in 0..pos.len() {
+            for j in (i + 1)..pos.len() {
+                let d = pos[j] - pos[i];
+                let r2 = d.dot(d) + eps2;
+                // dos cuerpos en el mismo punto sin suavizado no tienen dirección
+                // entre ellos; sin este corte la fuerza sería inf * 0 = NaN
+                if r2 == 0.0 { continue; }
+                let inv_r3 = 1.0 / (r2 * r2.sqrt());
+                acc[i] = acc[i] + d * (self.gravity * self.mass[j] * inv_r3);
+                acc[j] = acc[j] - d * (self.gravity * self.mass[i] * inv_r3);
+            }
+        }
+        acc
+    }
+
+    fn step_leapfrog(&mut self) {
+        let h = self.dt;
+        let acc = self.accelerations(&self.pos);
+        for (v, a) in self.vel.iter_mut().zip(&acc) {
+            *v = *v + *a * (0.5 * h);
+        }
+        for (p, v) in self.pos.iter_mut().zip(&self.vel) {
+            *p = *p + *v * h;
+        }
+        let acc = self.accelerations(&self.pos);
+        for (v, a) in self.vel.iter_mut().zip(&acc) {
+            *v = *v + *a * (0.5 * h);
+        }
+    }
+
+    fn step_rk4(&mut self) {
+        let h = self.dt;
+        let offset = |base: &[Vec3], d: &[Vec3], s: f32| -> Vec<Vec3> {
+            base.iter().zip(d).map(|(b, d)| *b + *d * s).collect()
+        };
+
+        let k1v = self.accelerations(&self.pos);
+        let k1x = self.vel.clone();
+
+        let k2v = self.accelerations(&offset(&self.pos, &k1x, 0.5 * h));
+        let k2x = offset(&self.vel, &k1v, 0.5 * h);
+
+        let k3v = self.accelerations(&offset(&self.pos, &k2x, 0.5 * h));
+        let k3x = offset(&self.vel, &k2v, 0.5 * h);
+
+        let k4v = self.accelerations(&offset(&self.pos, &k3x, h));
+        let k4x = offset(&self.vel, &k3v, h);
+
+        for i in 0..self.pos.len() {
+            self.pos[i] = self.pos[i] + (k1x[i] + k2x[i] * 2.0 + k3x[i] * 2.0 + k4x[i]) * (h / 6.0);
+            self.vel[i] = self.vel[i] + (k1v[i] + k2v[i] * 2.0 + k3v[i] * 2.0 + k4v[i]) * (h / 6.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coincident_bodies_stay_finite() {
+        for integrator in [Integrator::Leapfrog, Integrator::Rk4] {
+            // una luna sin órbita empieza encima de su padre; el tercero hace de testigo
+            let pos = vec![Vec3::zero(), Vec3::zero(), Vec3::new(5.0, 0.0, 0.0)];
+            let vel = vec![Vec3::zero(), Vec3::new(0.0, 0.0, 0.5), Vec3::new(0.0, 0.0, 1.0)];
+            let mut sim = Simulation::new(1.0, 0.01, 0.0, integrator, vec![10.0, 0.0, 1.0], pos, vel);
+            sim.advance_to(1.0);
+            for p in &sim.pos {
+                assert!(p.x.is_finite() && p.y.is_finite() && p.z.is_finite(), "{:?}: {:?}", integrator, sim.pos);
+            }
+        }
+    }
+}
@@ -1,56 +1,76 @@
-use glam::Vec3;
-use image::{Rgb, RgbImage};
-use crate::shader::shade;
-use crate::scene::{SolarSystem, Planet};
-
-pub fn render_frame(system: &SolarSystem, _time: f32) -> RgbImage {
-    let size = system.size;
-    let mut img = RgbImage::new(size, size);
-    let bg = Rgb([5, 5, 15]); // fondo oscuro
-
-    // fondo
-    for y in 0..size {
-        for x in 0..size {
-            img.put_pixel(x, y, bg);
+// src/renderer.rs
+// Vista previa 2D: cada cuerpo visto de frente en proyección ortográfica, sobre
+// una textura cuadrada (las miniaturas del catálogo, como out/star.png).
+use rayon::prelude::*;
+
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::body::Body;
+use crate::hittable::{Hit, Hittable};
+use crate::color::to_u32;
+use crate::scene::MAX_LAYERS;
+
+/// Renderiza `body` tal como está colocado y girado ahora mismo (llamar antes a
+/// `Scene::update`). Devuelve `size * size` píxeles 0x00RRGGBB; el cuerpo,
+/// anillo incluido, ocupa casi todo el cuadro.
+pub fn render_planet(body: &Body, size: usize, time: f32) -> Vec<u32> {
+    let center = body.sphere.center;
+    let extent = 1.05 * body.ring.as_ref().map_or(body.sphere.radius, |r| r.outer.max(body.sphere.radius));
+
+    let mut buffer = vec![0u32; size * size];
+    buffer.par_chunks_mut(size).enumerate().for_each(|(y, row)| {
+        for (x, px_out) in row.iter_mut().enumerate() {
+            let u = (2.0 * ((x as f32 + 0.5) / size as f32) - 1.0) * extent;
+            let v = (1.0 - 2.0 * ((y as f32 + 0.5) / size as f32)) * extent;
+
+            // rayos paralelos a +Z, como la cámara por defecto de la escena
+            let ray = Ray::new(center + Vec3::new(u, v, -2.0 * extent), Vec3::new(0.0, 0.0, 1.0));
+            let c = radiance(body, ray, time);
+            *px_out = to_u32(Vec3::new(c.x.sqrt(), c.y.sqrt(), c.z.sqrt()));
         }
-    }
+    });
+    buffer
+}
 
-    // renderiza cada planeta
-    for planet in &system.planets {
-        render_planet(&mut img, planet);
-    }
+fn nearest(body: &Body, ray: &Ray, time: f32) -> Option<Hit> {
+    let sphere = body.sphere.hit(ray, f32::INFINITY, time);
+    let t_max = sphere.as_ref().map_or(f32::INFINITY, |h| h.t);
+    let ring = body.ring.as_ref().and_then(|r| r.hit(ray, t_max, time)).or(sphere);
+    let t_max = ring.as_ref().map_or(f32::INFINITY, |h| h.t);
+    body.clouds.as_ref().and_then(|c| c.hit(ray, t_max, time)).or(ring)
+}
+
+fn radiance(body: &Body, mut ray: Ray, time: f32) -> Vec3 {
+    // fondo oscuro uniforme, pensado ya con gamma
+    let bg = Vec3::new(0.02, 0.02, 0.06);
+    let bg = bg * bg;
+    let mut color = Vec3::zero();
+    let mut through = 1.0;
 
-    img
+    for _ in 0..MAX_LAYERS {
+        let Some(hit) = nearest(body, &ray, time) else { break; };
+        color = color + light(body, &hit, &ray) * (hit.opacity * through);
+        through *= 1.0 - hit.opacity;
+        if through <= 1e-3 { return color; }
+        ray = Ray::new(hit.point + ray.dir * 1e-3, ray.dir);
+    }
+    color + bg * through
 }
 
-fn render_planet(img: &mut RgbImage, planet: &Planet) {
-    let size = img.width();
-    let (crot, srot) = (planet.rotation.cos(), planet.rotation.sin());
-
-    for y in 0..size {
-        for x in 0..size {
-            let pos = Vec3::new(x as f32, y as f32, 0.0);
-            let dir = pos - planet.position;
-            let dist = dir.length();
-
-            if dist <= planet.radius {
-                // rotación en Z manual
-                let v = dir / planet.radius;
-                let nx = v.x * crot - v.y * srot;
-                let ny = v.x * srot + v.y * crot;
-                let nz = v.z;
-                let n = Vec3::new(nx, ny, nz);
-
-                let color = shade(
-                    planet.color_shader,
-                    n,
-                    dist / planet.radius,
-                    pos,
-                    planet.position,
-                    size,
-                );
-                img.put_pixel(x, y, color);
-            }
-        }
+/// Luz fija de estudio: las estrellas brillan por sí solas (con oscurecimiento
+/// hacia el limbo), el resto recibe una luz desde arriba a la izquierda para que
+/// se vea el relieve.
+fn light(body: &Body, hit: &Hit, ray: &Ray) -> Vec3 {
+    if body.sphere.is_light {
+        let mu = hit.normal.dot(-ray.dir).max(0.0);
+        return (hit.color + hit.emissive) * (0.55 + 0.45 * mu);
     }
+    let key = Vec3::new(-0.6, 0.5, -0.62).normalize();
+    let ambient = Vec3::new(0.06, 0.06, 0.07);
+
+    let lam = hit.normal.dot(key).max(0.0);
+    let half = (key - ray.dir).normalize();
+    let spec = hit.normal.dot(half).max(0.0).powf(hit.shininess) * hit.specular;
+    hit.color * (ambient + Vec3::new(1.0, 1.0, 1.0) * lam) + Vec3::new(spec, spec, spec) + hit.emissive
+        + hit.night_glow(lam)
 }
@@ -0,0 +1,131 @@
+// src/ring.rs
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::noise::smoothstep;
+use crate::hittable::{Hit, Hittable};
+
+/// Hueco en el anillo (como la división de Cassini), con bordes suaves.
+#[derive(Copy, Clone, Debug)]
+pub struct RingGap {
+    pub radius: f32,
+    pub width: f32,
+}
+
+/// Anillo plano (corona circular) centrado en un cuerpo.
+///
+/// Los perfiles se evalúan sobre `u`, la posición radial normalizada entre el
+/// borde interior (0) y el exterior (1); cada uno es una lista de paradas
+/// `(u, valor)` ordenadas, interpoladas linealmente.
+pub struct Ring {
+    /// se mueve junto con el cuerpo dueño
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub inner: f32,
+    pub outer: f32,
+    /// opacidad máxima, se multiplica por la densidad local
+    pub opacity: f32,
+    pub colors: Vec<(f32, Vec3)>,
+    pub density: Vec<(f32, f32)>,
+    /// frecuencia y contraste de las bandas finas
+    pub bands: f32,
+    pub band_contrast: f32,
+    pub gaps: Vec<RingGap>,
+}
+
+impl Ring {
+    /// Normal del plano a partir de la inclinación respecto a +Y y la dirección
+    /// (azimut en XZ) hacia la que se inclina, ambos en radianes.
+    pub fn tilt_normal(tilt: f32, direction: f32) -> Vec3 {
+        Vec3::new(tilt.sin() * direction.cos(), tilt.cos(), tilt.sin() * direction.sin())
+    }
+
+    /// Distancia al centro medida dentro del plano del anillo.
+    fn radial(&self, p: Vec3) -> f32 {
+        let v = p - self.center;
+        (v - self.normal * v.dot(self.normal)).length()
+    }
+
+    /// Densidad en el radio `r`, entre 0 (vacío) y 1.
+    pub fn density_at(&self, r: f32) -> f32 {
+        if r <= self.inner || r >= self.outer { return 0.0; }
+        let u = (r - self.inner) / (self.outer - self.inner);
+
+        // patrón bandado para el anillo (sin texturas)
+        let band = (u * self.bands).sin().abs();
+        let mut d = profile(&self.density, u, 1.0) * (1.0 - self.band_contrast * (1.0 - band));
+
+        for gap in &self.gaps {
+            d *= smoothstep(0.0, 0.5 * gap.width, (r - gap.radius).abs());
+        }
+        d.clamp(0.0, 1.0)
+    }
+
+    /// Fracción de luz que bloquea el anillo en el radio `r`.
+    pub fn alpha_at(&self, r: f32) -> f32 {
+        self.opacity * self.density_at(r)
+    }
+
+    pub fn color_at(&self, r: f32) -> Vec3 {
+        let u = (r - self.inner) / (self.outer - self.inner);
+        profile(&self.colors, u, Vec3::new(0.86, 0.82, 0.72))
+    }
+}
+
+/// Interpola linealmente una lista de paradas ordenadas por `u`.
+fn profile<T>(stops: &[(f32, T)], u: f32, default: T) -> T
+where
+    T: Copy + std::ops::Mul<f32, Output = T> + std::ops::Add<Output = T>,
+{
+    let Some(first) = stops.first() else { return default; };
+    if u <= first.0 { return first.1; }
+    for w in stops.windows(2) {
+        let (u0, a) = w[0];
+        let (u1, b) = w[1];
+        if u <= u1 {
+            let k = if u1 > u0 { (u - u0) / (u1 - u0) } else { 1.0 };
+            return a * (1.0 - k) + b * k;
+        }
+    }
+    stops[stops.len() - 1].1
+}
+
+impl Hittable for Ring {
+    fn hit(&self, ray: &Ray, t_max: f32, _time: f32) -> Option<Hit> {
+        let t = self.distance(ray, t_max)?;
+        let point = ray.at(t);
+        let r = self.radial(point);
+
+        // plano de dos caras: la normal mira hacia quien lo ve
+        let normal = if ray.dir.dot(self.normal) > 0.0 { -self.normal } else { self.normal };
+        Some(Hit {
+            t,
+            point,
+            normal,
+            // el anillo es fino y dispersa mucho: se aclara para que destaque
+            color: self.color_at(r) * 1.8,
+            emissive: Vec3::zero(),
+            night: Vec3::zero(),
+            specular: 0.25,
+            shininess: 8.0,
+            opacity: self.alpha_at(r),
+        })
+    }
+
+    fn distance(&self, ray: &Ray, t_max: f32) -> Option<f32> {
+        // intersectar rayo con plano del anillo
+        let denom = ray.dir.dot(self.normal);
+        if denom.abs() <= 1e-6 { return None; }
+        let t = (self.center - ray.orig).dot(self.normal) / denom;
+        if t <= 1e-4 || t >= t_max { return None; }
+
+        if self.alpha_at(self.radial(ray.at(t))) > 0.0 { Some(t) } else { None }
+    }
+
+    fn transmittance(&self, ray: &Ray, t_max: f32) -> f32 {
+        // sombra parcial: las bandas y huecos dejan pasar luz según su densidad
+        match self.distance(ray, t_max) {
+            Some(t) => 1.0 - self.alpha_at(self.radial(ray.at(t))),
+            None => 1.0,
+        }
+    }
+}
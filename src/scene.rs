@@ -1,192 +1,335 @@
 // src/scene.rs
+use std::path::Path;
+
 use rayon::prelude::*;
 
 use crate::vec3::Vec3;
 use crate::ray::Ray;
-use crate::sphere::{Sphere, SphereKind};
+use crate::sphere::{sphere_span, Sphere};
+use crate::body::{Body, BodyId};
+use crate::atmosphere::{Atmosphere, Sun};
+use crate::hittable::{Hit, Hittable};
+use crate::physics::{Integrator, Simulation};
 use crate::color::to_u32;
+use crate::noise::hash1;
+use crate::scene_file::{self, SceneError};
+
+/// Escena que se usa cuando no se pasa ningún archivo por línea de comandos.
+const DEFAULT_SCENE: &str = include_str!("../scenes/sistema.toml");
+
+/// Máximo de superficies translúcidas que atraviesa un rayo antes de cortar.
+pub(crate) const MAX_LAYERS: u32 = 4;
+
+/// Atenuación de la luz de una estrella con la distancia (simple, no física).
+pub fn light_falloff(dist: f32) -> f32 {
+    1.0 / (0.5 + 0.1 * dist * dist)
+}
+
+/// Muestra `i` de `n` sobre el disco unidad: r² estratificado en `n` franjas de
+/// igual área con jitter, y el ángulo avanzando por el ángulo áureo desde un giro
+/// que depende de `seed`. Cada franja recibe exactamente una muestra, sea `n` el
+/// que sea, y ninguna dirección del disco queda favorecida.
+fn disk_sample(i: u32, n: u32, seed: u32) -> (f32, f32) {
+    const GOLDEN_ANGLE: f32 = 2.399_963;
+    let r = ((i as f32 + hash1(seed.wrapping_add(i + 1))) / n as f32).sqrt();
+    let theta = std::f32::consts::TAU * hash1(seed) + GOLDEN_ANGLE * i as f32;
+    (r * theta.cos(), r * theta.sin())
+}
 
 pub struct Scene {
     pub width: usize,
     pub height: usize,
     pub camera_pos: Vec3,
-    pub spheres: Vec<Sphere>,
+    /// punto al que mira la cámara (con +Y como arriba)
+    pub camera_target: Vec3,
+    pub bodies: Vec<Body>,
+    /// objetos sueltos que no pertenecen a ningún cuerpo (mallas, SDF...)
+    pub objects: Vec<Box<dyn Hittable>>,
     pub fov: f32,
+    pub time_scale: f32,
+    /// rayos de sombra por luz; 1 = luz puntual, más = penumbras suaves
+    pub shadow_samples: u32,
+    /// si existe, las posiciones salen de la simulación gravitatoria y no de las órbitas
+    pub physics: Option<Simulation>,
 }
 
 impl Scene {
     pub fn new(width: usize, height: usize) -> Self {
-        let camera_pos = Vec3::new(0.0, 0.0, -9.0);
+        scene_file::parse(DEFAULT_SCENE, "scenes/sistema.toml", width, height)
+            .expect("la escena por defecto debe ser válida")
+    }
+
+    pub fn from_file(path: &Path, width: usize, height: usize) -> Result<Self, SceneError> {
+        scene_file::load(path, width, height)
+    }
 
-        let spheres = vec![
-            // Estrella (luz)
-            Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.4, SphereKind::Star, true, 0.0),
+    /// Como `from_file`, pero con el TOML ya en memoria.
+    pub fn from_toml(src: &str, width: usize, height: usize) -> Result<Self, SceneError> {
+        scene_file::parse(src, "<toml>", width, height)
+    }
 
-            // Planeta Rocoso
-            Sphere::new(Vec3::new(-3.0, 0.0, 1.0), 1.0, SphereKind::Rocky, false, 0.2),
+    /// Busca un cuerpo por nombre.
+    pub fn find(&self, name: &str) -> Option<BodyId> {
+        self.bodies.iter().position(|b| b.name == name).map(BodyId)
+    }
 
-            // Luna (opcional, pequeña)
-            Sphere::new(Vec3::new(-2.2, 0.0, 1.6), 0.28, SphereKind::Moon, false, 0.9),
+    pub fn body(&self, id: BodyId) -> &Body {
+        &self.bodies[id.0]
+    }
 
-            // Gigante Gaseoso (con anillos calculados proceduralmente)
-            Sphere::new(Vec3::new(3.0, 0.5, 1.5), 1.0, SphereKind::GasGiant, false, 1.0),
-        ];
+    pub fn body_mut(&mut self, id: BodyId) -> &mut Body {
+        &mut self.bodies[id.0]
+    }
 
-        Scene { width, height, camera_pos, spheres, fov: 1.0 }
+    fn lights(&self) -> impl Iterator<Item = &Sphere> {
+        self.bodies.iter().map(|b| &b.sphere).filter(|s| s.is_light)
     }
 
-    pub fn trace(&self, ray: &Ray, time: f32) -> Vec3 {
-        // 1) buscar la intersección de esferas más cercana
-        let mut nearest_t = f32::INFINITY;
-        let mut hit_sphere: Option<&Sphere> = None;
-
-        for s in &self.spheres {
-            if let Some(t) = s.intersect(ray) {
-                if t < nearest_t {
-                    nearest_t = t;
-                    hit_sphere = Some(s);
-                }
+    /// Todo lo intersectable: esfera, anillo y nubes de cada cuerpo más los objetos sueltos.
+    pub fn hittables(&self) -> impl Iterator<Item = &dyn Hittable> {
+        self.bodies.iter()
+            .flat_map(|b| {
+                let ring = b.ring.as_ref().map(|r| r as &dyn Hittable);
+                let clouds = b.clouds.as_ref().map(|c| c as &dyn Hittable);
+                std::iter::once(&b.sphere as &dyn Hittable).chain(ring).chain(clouds)
+            })
+            .chain(self.objects.iter().map(|o| o.as_ref()))
+    }
+
+    /// Impacto más cercano de un rayo contra toda la escena.
+    pub fn hit(&self, ray: &Ray, time: f32) -> Option<Hit> {
+        let mut nearest: Option<Hit> = None;
+        for obj in self.hittables() {
+            let t_max = nearest.as_ref().map_or(f32::INFINITY, |h| h.t);
+            if let Some(h) = obj.hit(ray, t_max, time) {
+                nearest = Some(h);
             }
         }
+        nearest
+    }
 
-        // 2) comprobar intersección con anillo 
-        if self.spheres.len() > 3 {
-            let gas = &self.spheres[3];
-            let ring_center = gas.center;
-            // plano del anillo: normal inclinada 
-            let ring_normal = Vec3::new(0.0, 2.0, 0.26).normalize();
-
-            // intersectar rayo con plano del anillo
-            let denom = ray.dir.dot(ring_normal);
-            if denom.abs() > 1e-6 {
-                let t_plane = (ring_center - ray.orig).dot(ring_normal) / denom;
-                if t_plane > 0.0 && t_plane < nearest_t {
-                    let hit_point = ray.at(t_plane);
-                    // proyectar punto al plano del anillo (sustrayendo componente normal)
-                    let v = hit_point - ring_center;
-                    let v_plane = v - ring_normal * v.dot(ring_normal);
-                    let r = v_plane.length();
-
-                    // radios del anillo relativos al radio del planeta gaseoso
-                    let inner = gas.radius * 1.35;
-                    let outer = gas.radius * 2.6;
-
-                    if r > inner && r < outer {
-                        // patrón bandado para el anillo (sin texturas)
-                        let bands = ((r - inner) / (outer - inner) * 40.0).sin().abs();
-                        // ligera variación radial para dar detalle
-                        let band_mask = 0.5 + 0.5 * bands;
-                        // color base del anillo y mezcla
-                        let ring_base = Vec3::new(0.86, 0.82, 0.72); // arena clara
-                        let ring_dark = Vec3::new(0.35, 0.32, 0.30); // bandas oscuras
-
-                        let mut ring_color = ring_base * (0.6 + 0.8 * band_mask) + ring_dark * (0.25 * (1.0 - band_mask));
-                        // añadir un brillo más fuerte en la parte iluminada por la estrella(s)
-                        // calcular iluminación simple desde luces (mismas luces que para esferas)
-                        let mut lighting = Vec3::zero();
-                        for light in &self.spheres {
-                            if light.is_light {
-                                let to_light = (light.center - hit_point);
-                                let light_dist = to_light.length();
-                                let ldir = to_light.normalize();
-
-                                // sombra
-                                let shadow_origin = hit_point + ring_normal * 0.0005;
-                                let shadow_ray = Ray::new(shadow_origin, ldir);
-                                let mut in_shadow = false;
-                                for other in &self.spheres {
-                                    if std::ptr::eq(other, light) { continue; }
-                                    if let Some(t_sh) = other.intersect(&shadow_ray) {
-                                        if t_sh < light_dist { in_shadow = true; break; }
-                                    }
-                                }
-                                if !in_shadow {
-                                    let lam = ring_normal.dot(ldir).max(0.0);
-                                    // atenuación
-                                    let att = 1.0 / (0.5 + 0.1 * light_dist * light_dist);
-                                    // tomar emisivo de la luz (star shader)
-                                    let (_ls, lemi) = light.shade(light.center, Vec3::new(0.0,1.0,0.0), time);
-                                    lighting = lighting + lemi * lam * att;
-                                }
-                            }
-                        }
-
-                        // mezclar color con lighting
-                        ring_color = ring_color * (Vec3::new(0.12, 0.12, 0.12) + lighting * 1.8);
-                        // un poco de brillo especular sutil basado en la orientación
-                        let view = (self.camera_pos - hit_point).normalize();
-                        let half = (view + (self.spheres[0].center - hit_point).normalize()).normalize();
-                        let spec = ring_normal.dot(half).max(0.0).powf(8.0) * 0.25;
-                        ring_color = ring_color + Vec3::new(spec, spec, spec);
-
-                        // gamma y clamp
-                        let final_ring = Vec3::new(ring_color.x.sqrt(), ring_color.y.sqrt(), ring_color.z.sqrt()).clamp(0.0, 1.0);
-                        return final_ring;
-                    }
-                }
-            }
+    /// Fracción de la luz de `light` que llega a `ray.orig` a lo largo de `dist`;
+    /// los objetos translúcidos (anillos) atenúan en vez de tapar del todo.
+    fn visibility(&self, ray: &Ray, dist: f32, light: &Sphere) -> f32 {
+        let mut vis = 1.0;
+        for obj in self.hittables() {
+            // saltar la propia luz
+            if std::ptr::addr_eq(obj as *const dyn Hittable, light as *const Sphere) { continue; }
+            vis *= obj.transmittance(ray, dist);
+            if vis <= 0.0 { return 0.0; }
         }
+        vis
+    }
 
-        // 3) si no golpeó anillo antes, proceder como antes con la esfera más cercana
-        if hit_sphere.is_none() {
-            let tbg = 0.5 * (ray.dir.y + 1.0);
-            return Vec3::new(0.05, 0.05, 0.08)*(1.0 - tbg) + Vec3::new(0.02, 0.03, 0.06)*tbg;
+    /// Coloca y orienta los cuerpos en el instante de simulación `t`.
+    pub fn update(&mut self, t: f32) {
+        if let Some(sim) = &mut self.physics {
+            sim.advance_to(t);
+            for (body, &p) in self.bodies.iter_mut().zip(&sim.pos) {
+                body.set_position(p);
+            }
+        } else {
+            self.update_orbits(t);
         }
+        self.update_spins(t);
+    }
 
-        let s = hit_sphere.unwrap();
-        let p = ray.at(nearest_t);
-        let n = (p - s.center).normalize();
+    /// Gira cada cuerpo sobre su eje; los que tienen acoplamiento de marea
+    /// miran siempre a su padre. Las nubes giran aparte, a su propio ritmo.
+    fn update_spins(&mut self, t: f32) {
+        let angles: Vec<f32> = self.bodies.iter()
+            .map(|b| match (b.tidal_lock, b.parent) {
+                (true, Some(p)) => b.sphere.spin_facing(self.bodies[p.0].sphere.center),
+                _ => b.sphere.rotate_speed * t,
+            })
+            .collect();
+        for (body, angle) in self.bodies.iter_mut().zip(angles) {
+            body.sphere.set_spin(angle);
+            if let Some(clouds) = &mut body.clouds {
+                clouds.update(t);
+            }
+        }
+    }
 
-        // obtener color base y componente emisiva desde el shader de la esfera
-        let (surf_col, surf_emissive) = s.shade(p, n, time);
+    /// Recorre el grafo desde las raíces y compone las posiciones de cada cuerpo
+    /// con la de su padre.
+    fn update_orbits(&mut self, t: f32) {
+        let mut stack: Vec<(BodyId, Vec3)> = self.bodies.iter().enumerate()
+            .filter(|(_, b)| b.parent.is_none())
+            .map(|(i, _)| (BodyId(i), Vec3::zero()))
+            .collect();
+
+        while let Some((id, parent_pos)) = stack.pop() {
+            let body = &mut self.bodies[id.0];
+            let pos = parent_pos + body.local_position(t);
+            body.set_position(pos);
+            stack.extend(body.children.iter().map(|&c| (c, pos)));
+        }
+    }
 
-        // iluminación: sumar contribución de cada luz (esferas con is_light = true)
-        let mut lighting = Vec3::zero();
-        for light in &self.spheres {
-            if light.is_light {
-                let to_light = (light.center - p);
-                let light_dist = to_light.length();
-                let ldir = to_light.normalize();
-
-                // rayito hacia la luz 
-                let shadow_origin = p + n * 0.001;
-                let shadow_ray = Ray::new(shadow_origin, ldir);
-
-                // verificar sombras
-                let mut in_shadow = false;
-                for other in &self.spheres {
-                    // saltar la propia luz
-                    if std::ptr::eq(other, light) { continue; }
-                    if let Some(t) = other.intersect(&shadow_ray) {
-                        if t < light_dist { in_shadow = true; break; }
-                    }
+    /// Pasa al modo de N cuerpos partiendo del estado de las órbitas en t = 0.
+    ///
+    /// Las velocidades iniciales componen las del padre; para cuerpos con órbita y
+    /// sin `velocity` explícita, la rapidez sale de la ecuación vis-viva con la masa
+    /// del padre, así la órbita inicial es kepleriana bajo la gravedad simulada.
+    pub fn enable_physics(&mut self, gravity: f32, dt: f32, softening: f32, integrator: Integrator) {
+        self.physics = None;
+        self.update_orbits(0.0);
+
+        let n = self.bodies.len();
+        let mut vel = vec![Vec3::zero(); n];
+        let mut stack: Vec<(BodyId, Vec3)> = self.bodies.iter().enumerate()
+            .filter(|(_, b)| b.parent.is_none())
+            .map(|(i, _)| (BodyId(i), Vec3::zero()))
+            .collect();
+
+        while let Some((id, parent_vel)) = stack.pop() {
+            let body = &self.bodies[id.0];
+            let local = match (body.velocity, &body.orbit, body.parent) {
+                (Some(v), _, _) => v,
+                (None, Some(orbit), Some(parent)) if self.bodies[parent.0].mass > 0.0 => {
+                    let mu = gravity * (self.bodies[parent.0].mass + body.mass);
+                    let r = (body.sphere.center - self.bodies[parent.0].sphere.center).length();
+                    let speed = (mu * (2.0 / r - 1.0 / orbit.semi_major_axis)).max(0.0).sqrt();
+                    body.local_velocity(0.0).normalize() * speed
                 }
+                _ => body.local_velocity(0.0),
+            };
+            vel[id.0] = parent_vel + local;
+            stack.extend(body.children.iter().map(|&c| (c, vel[id.0])));
+        }
 
-                if !in_shadow {
-                    // Lambert
-                    let lam = n.dot(ldir).max(0.0);
-                    // atenuación por distancia (simple)
-                    let att = 1.0 / (0.5 + 0.1 * light_dist * light_dist);
-                    // la propia luz puede aportar color emisivo (shader de la luz)
-                    let (_l_surf, l_emissive) = light.shade(light.center, Vec3::new(0.0, 1.0, 0.0), time);
-                    lighting = lighting + l_emissive * lam * att;
-
-                    // specular pequeño
-                    let view = (self.camera_pos - p).normalize();
-                    let half = (view + ldir).normalize();
-                    let spec = n.dot(half).max(0.0).powf(40.0) * 0.2;
-                    lighting = lighting + Vec3::new(1.0, 1.0, 1.0) * spec * att;
-                }
-            }
+        let mass = self.bodies.iter().map(|b| b.mass).collect();
+        let pos = self.bodies.iter().map(|b| b.sphere.center).collect();
+        self.physics = Some(Simulation::new(gravity, dt, softening, integrator, mass, pos, vel));
+    }
+
+    /// Visibilidad de la estrella `light` vista como disco desde `origin`.
+    ///
+    /// Se muestrea el disco que la esfera presenta hacia el punto (ver
+    /// `disk_sample`), así los eclipses tienen umbra y penumbra en vez de un
+    /// borde duro.
+    fn area_visibility(&self, origin: Vec3, light: &Sphere) -> f32 {
+        let to_light = light.center - origin;
+        let dist = to_light.length();
+        let w = to_light / dist;
+        if self.shadow_samples <= 1 {
+            return self.visibility(&Ray::new(origin, w), dist, light);
+        }
+
+        // base ortonormal del disco, perpendicular a la dirección de la luz
+        let helper = if w.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let u = w.cross(helper).normalize();
+        let v = w.cross(u);
+
+        let n = self.shadow_samples;
+        let seed = origin.x.to_bits() ^ origin.y.to_bits().rotate_left(11) ^ origin.z.to_bits().rotate_left(22);
+        let mut sum = 0.0;
+        for i in 0..n {
+            let (du, dv) = disk_sample(i, n, seed);
+            let target = light.center + (u * du + v * dv) * light.radius;
+            let to_target = target - origin;
+            sum += self.visibility(&Ray::new(origin, to_target), to_target.length(), light);
+        }
+        sum / n as f32
+    }
+
+    pub fn trace(&self, ray: &Ray, time: f32) -> Vec3 {
+        let c = self.radiance(ray, time, 0);
+        // gamma
+        Vec3::new(c.x.sqrt(), c.y.sqrt(), c.z.sqrt()).clamp(0.0, 1.0)
+    }
+
+    /// Color lineal (antes de gamma) que llega por `ray`. `depth` cuenta las capas
+    /// translúcidas ya atravesadas.
+    fn radiance(&self, ray: &Ray, time: f32, depth: u32) -> Vec3 {
+        // 1) buscar la intersección más cercana entre todos los objetos
+        let Some(hit) = self.hit(ray, time) else {
+            let tbg = 0.5 * (ray.dir.y + 1.0);
+            let bg = Vec3::new(0.05, 0.05, 0.08)*(1.0 - tbg) + Vec3::new(0.02, 0.03, 0.06)*tbg;
+            // el fondo está pensado ya con gamma
+            return self.through_air(ray, f32::INFINITY, bg * bg, time);
+        };
+        let color = self.shade(ray, &hit, time, depth);
+        self.through_air(ray, hit.t, color, time)
+    }
+
+    /// Color lineal de la superficie `hit` vista desde `ray`, sin el aire de por medio.
+    fn shade(&self, ray: &Ray, hit: &Hit, time: f32, depth: u32) -> Vec3 {
+        let p = hit.point;
+        let n = hit.normal;
+        let view = (self.camera_pos - p).normalize();
+
+        // 2) iluminación: sumar contribución de cada luz (esferas con is_light = true)
+        let mut lighting = Vec3::zero();
+        let mut specular = Vec3::zero();
+        for light in self.lights() {
+            let to_light = light.center - p;
+            let light_dist = to_light.length();
+            let ldir = to_light.normalize();
+
+            // rayitos hacia el disco de la luz
+            let vis = self.area_visibility(p + n * 0.001, light);
+            if vis <= 0.0 { continue; }
+
+            // Lambert
+            let lam = n.dot(ldir).max(0.0);
+            // atenuación por distancia (simple) y por el aire que cruza la luz,
+            // que la enrojece cerca del terminador
+            let att = self.air_transmittance(&Ray::new(p + n * 0.001, ldir), light_dist)
+                * (vis * light_falloff(light_dist));
+            // la propia luz puede aportar color emisivo (shader de la luz)
+            lighting = lighting + light.emission(time) * lam * att;
+
+            // specular pequeño
+            let half = (view + ldir).normalize();
+            let spec = n.dot(half).max(0.0).powf(hit.shininess) * hit.specular;
+            specular = specular + att * spec;
         }
 
         // ambient
         let ambient = Vec3::new(0.06, 0.06, 0.07);
 
-        let final_color = surf_col * (ambient + lighting) + surf_emissive;
+        // las luces nocturnas se apagan a medida que les da la estrella
+        let night = hit.night_glow(lighting.x.max(lighting.y).max(lighting.z));
+        let final_color = hit.color * (ambient + lighting) + specular + hit.emissive + night;
 
-        // gamma
-        Vec3::new(final_color.x.sqrt(), final_color.y.sqrt(), final_color.z.sqrt()).clamp(0.0, 1.0)
+        // 3) capas translúcidas (anillos): mezclar con lo que hay detrás
+        if hit.opacity < 1.0 && depth < MAX_LAYERS {
+            let behind = self.radiance(&Ray::new(p + ray.dir * 1e-3, ray.dir), time, depth + 1);
+            return final_color * hit.opacity + behind * (1.0 - hit.opacity);
+        }
+        final_color
+    }
+
+    /// Atmósferas que cruza `ray` antes de `t_end`, de la más lejana a la más cercana.
+    fn atmospheres_along(&self, ray: &Ray, t_end: f32) -> Vec<&Atmosphere> {
+        let mut found: Vec<(f32, &Atmosphere)> = self.bodies.iter()
+            .filter_map(|b| b.atmosphere.as_ref())
+            .filter_map(|a| sphere_span(ray, a.center, a.radius)
+                .filter(|&(t0, t1)| t1 > 0.0 && t0 < t_end)
+                .map(|(t0, _)| (t0, a)))
+            .collect();
+        found.sort_by(|a, b| b.0.total_cmp(&a.0));
+        found.into_iter().map(|(_, a)| a).collect()
+    }
+
+    /// Aplica a `color`, que llega desde `t_end`, el aire que hay por el camino:
+    /// lo atenúa y le suma la luz de las estrellas dispersada hacia la cámara.
+    fn through_air(&self, ray: &Ray, t_end: f32, color: Vec3, time: f32) -> Vec3 {
+        let atmospheres = self.atmospheres_along(ray, t_end);
+        if atmospheres.is_empty() { return color; }
+        let suns: Vec<Sun> = self.lights()
+            .map(|l| Sun { center: l.center, radius: l.radius, emission: l.emission(time) })
+            .collect();
+        atmospheres.iter().fold(color, |c, a| a.scatter(ray, t_end, c, &suns))
+    }
+
+    /// Transmitancia por canal del aire entre `ray.orig` y `dist` más allá.
+    fn air_transmittance(&self, ray: &Ray, dist: f32) -> Vec3 {
+        self.bodies.iter()
+            .filter_map(|b| b.atmosphere.as_ref())
+            .fold(Vec3::new(1.0, 1.0, 1.0), |t, a| t * a.transmittance(ray, dist))
     }
 
     pub fn render(&mut self, buffer: &mut [u32], time: f32) {
@@ -196,49 +339,70 @@ impl Scene {
         let half_height = (self.fov / 2.0).tan();
         let half_width = aspect * half_height;
 
-        let t = time * 0.9; // velocidad general
+        let t = time * self.time_scale; // velocidad general
 
         // ORBITAS y posiciones animadas
-        if self.spheres.len() >= 4 {
-            let sun = self.spheres[0].center;
-
-            // Planeta rocoso (índice 1) órbita circular alrededor del sol
-            let rock_orbit_radius = 3.0;
-            self.spheres[1].center = Vec3::new(
-                sun.x + rock_orbit_radius * (t * 1.0).cos(),
-                sun.y,
-                sun.z + rock_orbit_radius * (t * 1.0).sin()
-            );
-
-            // Luna (índice 2) orbita al planeta rocoso
-            let rock = self.spheres[1].center;
-            self.spheres[2].center = Vec3::new(
-                rock.x + 1.4 * (t * 2.2).cos(),
-                rock.y + 0.65 * (t * 1.6).sin(),
-                rock.z + 0.9 * (t * 2.2).sin()
-            );
-
-            // Gigante gaseoso (índice 3) órbita más lenta, más lejano
-            let gas_orbit_radius = 6.0;
-            self.spheres[3].center = Vec3::new(
-                sun.x + gas_orbit_radius * (t * 0.4).cos(),
-                sun.y - 0.6,
-                sun.z + gas_orbit_radius * (t * 0.4).sin()
-            );
-        }
+        self.update(t);
+
+        // base de la cámara
+        let forward = (self.camera_target - self.camera_pos).normalize();
+        let world_up = if forward.y.abs() > 0.999 { Vec3::new(0.0, 0.0, 1.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+        let right = world_up.cross(forward).normalize();
+        let up = forward.cross(right);
 
         // render en paralelo: cada chunk será una fila de width píxeles
         buffer.par_chunks_mut(width).enumerate().for_each(|(j, row)| {
             let y = j;
-            for i in 0..width {
+            for (i, px_out) in row.iter_mut().enumerate() {
                 let px = (2.0 * ((i as f32 + 0.5) / width as f32) - 1.0) * half_width;
                 let py = (1.0 - 2.0 * ((y as f32 + 0.5) / height as f32)) * half_height;
 
-                let dir = Vec3::new(px, py, 1.0).normalize();
+                let dir = (forward + right * px + up * py).normalize();
                 let ray = Ray::new(self.camera_pos, dir);
                 let col = self.trace(&ray, time);
-                row[i] = to_u32(col);
+                *px_out = to_u32(col);
             }
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_samples_stay_centred() {
+        // con 8 muestras la rejilla 3x3 de antes dejaba una celda sin visitar
+        let n = 8;
+        let seeds = 4096;
+        let (mut sx, mut sy) = (0.0f64, 0.0f64);
+        for seed in 0..seeds {
+            for i in 0..n {
+                let (x, y) = disk_sample(i, n, mix_seed(seed));
+                assert!(x * x + y * y <= 1.0 + 1e-5);
+                sx += x as f64;
+                sy += y as f64;
+            }
+        }
+        let count = (seeds * n) as f64;
+        let (mx, my) = (sx / count, sy / count);
+        assert!(mx.hypot(my) < 0.02, "media descentrada: ({}, {})", mx, my);
+    }
+
+    #[test]
+    fn disk_samples_cover_every_ring() {
+        for n in [2, 3, 5, 7, 8, 16] {
+            let mut rings = vec![0; n as usize];
+            for i in 0..n {
+                let (x, y) = disk_sample(i, n, 12345);
+                let ring = ((x * x + y * y) * n as f32).floor().min(n as f32 - 1.0) as usize;
+                rings[ring] += 1;
+            }
+            assert!(rings.iter().all(|&c| c == 1), "n = {}: {:?}", n, rings);
+        }
+    }
+
+    fn mix_seed(s: u32) -> u32 {
+        s.wrapping_mul(0x9e37_79b9)
+    }
+}
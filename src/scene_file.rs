@@ -0,0 +1,728 @@
+// src/scene_file.rs
+// Carga de escenas declarativas en TOML (ver scenes/sistema.toml)
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::vec3::Vec3;
+use crate::sphere::{Sphere, SphereKind};
+use crate::orbit::Orbit;
+use crate::body::{Body, BodyId};
+use crate::ring::{Ring, RingGap};
+use crate::atmosphere::Atmosphere;
+use crate::clouds::Clouds;
+use crate::scene::Scene;
+use crate::physics::Integrator;
+use crate::noise::{self, Basis, Noise};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io { origin: String, source: std::io::Error },
+    Parse { origin: String, source: toml::de::Error },
+    Invalid { origin: String, entry: String, message: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { origin, source } => write!(f, "{}: no se pudo leer: {}", origin, source),
+            SceneError::Parse { origin, source } => write!(f, "{}: {}", origin, source),
+            SceneError::Invalid { origin, entry, message } => write!(f, "{}: {}: {}", origin, entry, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneDesc {
+    #[serde(default)]
+    camera: CameraDesc,
+    #[serde(default = "default_time_scale")]
+    time_scale: f32,
+    /// semilla global: los cuerpos sin `seed` propia derivan la suya de ésta y su nombre
+    #[serde(default)]
+    seed: u32,
+    #[serde(default)]
+    render: RenderDesc,
+    #[serde(default)]
+    physics: PhysicsDesc,
+    #[serde(default)]
+    bodies: Vec<BodyDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenderDesc {
+    /// rayos de sombra por luz (1 = sombras duras)
+    #[serde(default = "default_shadow_samples")]
+    shadow_samples: u32,
+}
+
+impl Default for RenderDesc {
+    fn default() -> Self {
+        RenderDesc { shadow_samples: default_shadow_samples() }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    /// cada cuerpo sigue su órbita kepleriana
+    Kepler,
+    /// los cuerpos se atraen entre sí y se integran paso a paso
+    Nbody,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PhysicsDesc {
+    #[serde(default = "default_mode")]
+    mode: Mode,
+    #[serde(default = "default_gravity")]
+    gravity: f32,
+    #[serde(default = "default_dt")]
+    dt: f32,
+    #[serde(default)]
+    softening: f32,
+    #[serde(default = "default_integrator")]
+    integrator: Integrator,
+}
+
+impl Default for PhysicsDesc {
+    fn default() -> Self {
+        PhysicsDesc {
+            mode: default_mode(),
+            gravity: default_gravity(),
+            dt: default_dt(),
+            softening: 0.0,
+            integrator: default_integrator(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDesc {
+    #[serde(default = "default_camera_pos")]
+    position: [f32; 3],
+    /// por defecto mira hacia +Z
+    look_at: Option<[f32; 3]>,
+    #[serde(default = "default_fov")]
+    fov: f32,
+}
+
+impl Default for CameraDesc {
+    fn default() -> Self {
+        CameraDesc { position: default_camera_pos(), look_at: None, fov: default_fov() }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BodyDesc {
+    name: String,
+    kind: SphereKind,
+    parent: Option<String>,
+    #[serde(default)]
+    position: [f32; 3],
+    radius: f32,
+    #[serde(default)]
+    light: bool,
+    /// radianes por unidad de tiempo; alternativa a rotation_period
+    rotate_speed: Option<f32>,
+    rotation_period: Option<f32>,
+    /// grados entre el eje de giro y +Y, y azimut hacia el que se inclina
+    #[serde(default)]
+    obliquity: f32,
+    #[serde(default)]
+    obliquity_direction: f32,
+    #[serde(default)]
+    tidal_lock: bool,
+    /// ruido base de los shaders: "simplex" (por defecto), "perlin" o "hash"
+    #[serde(default)]
+    noise: Basis,
+    /// fija la superficie exacta del cuerpo; sin ella sale de la semilla de la escena
+    seed: Option<u32>,
+    /// probabilidad de cráter por celda, entre 0 y 1 (por defecto según `kind`)
+    crater_density: Option<f32>,
+    /// intensidad de las cordilleras (rocosos) y de las tormentas (gaseosos)
+    ridges: Option<f32>,
+    storms: Option<f32>,
+    /// rotación diferencial (rad por unidad de tiempo que pierden los polos) y
+    /// velocidad a la que evoluciona el ruido animado de gaseosos y estrellas
+    differential: Option<f32>,
+    flow: Option<f32>,
+    /// exageración del relieve al sombrear (0 = liso, 1 = pendientes reales)
+    bump: Option<f32>,
+    /// si es > 0, el relieve (multiplicado por esto) deforma de verdad la
+    /// superficie, que se dibuja por raymarching; sólo para rocky y moon
+    displacement: Option<f32>,
+    /// altura de los continentes (0 a 1) bajo la que hay mar y fracción de cada
+    /// hemisferio cubierta por los casquetes polares; sólo para rocky
+    sea_level: Option<f32>,
+    ice_caps: Option<f32>,
+    /// brillo de lo que luce en el lado nocturno: ciudades (rocky), grietas de
+    /// lava (rocky y moon) y auroras (rocky y gas_giant)
+    city_lights: Option<f32>,
+    lava: Option<f32>,
+    aurora: Option<f32>,
+    #[serde(default)]
+    mass: f32,
+    velocity: Option<[f32; 3]>,
+    orbit: Option<OrbitDesc>,
+    ring: Option<RingDesc>,
+    clouds: Option<CloudsDesc>,
+    atmosphere: Option<AtmosphereDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrbitDesc {
+    semi_major_axis: f32,
+    #[serde(default)]
+    eccentricity: f32,
+    /// los ángulos se escriben en grados
+    #[serde(default)]
+    inclination: f32,
+    #[serde(default)]
+    ascending_node: f32,
+    #[serde(default)]
+    argument_of_periapsis: f32,
+    #[serde(default)]
+    mean_anomaly: f32,
+    /// obligatorio en modo kepler; en modo nbody se deduce de las masas si falta
+    period: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RingDesc {
+    inner: f32,
+    outer: f32,
+    /// grados respecto a +Y y azimut hacia el que se inclina; sin tilt,
+    /// el anillo queda en el ecuador del cuerpo
+    tilt: Option<f32>,
+    #[serde(default)]
+    tilt_direction: f32,
+    #[serde(default = "default_ring_opacity")]
+    opacity: f32,
+    #[serde(default)]
+    colors: Vec<ColorStop>,
+    #[serde(default)]
+    density: Vec<DensityStop>,
+    #[serde(default = "default_ring_bands")]
+    bands: f32,
+    #[serde(default = "default_band_contrast")]
+    band_contrast: f32,
+    #[serde(default)]
+    gaps: Vec<GapDesc>,
+}
+
+/// Lo que falte toma los valores de `Clouds::new`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CloudsDesc {
+    /// altura sobre el suelo, en radios del cuerpo
+    #[serde(default = "default_cloud_altitude")]
+    altitude: f32,
+    coverage: Option<f32>,
+    opacity: Option<f32>,
+    /// radianes por unidad de tiempo; por defecto el 80 % del giro del cuerpo
+    rotate_speed: Option<f32>,
+    flow: Option<f32>,
+    color: Option<[f32; 3]>,
+}
+
+/// Longitudes en radios del cuerpo: los coeficientes son por radio y las
+/// alturas, fracciones del radio. Los valores por defecto dan un aire tipo
+/// Tierra, más alto de la cuenta para que se vea desde lejos.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtmosphereDesc {
+    #[serde(default = "default_rayleigh")]
+    rayleigh: [f32; 3],
+    #[serde(default = "default_rayleigh_height")]
+    rayleigh_height: f32,
+    #[serde(default = "default_mie")]
+    mie: f32,
+    #[serde(default = "default_mie_height")]
+    mie_height: f32,
+    #[serde(default = "default_mie_g")]
+    mie_g: f32,
+    /// grosor de la capa; por defecto seis alturas de escala
+    thickness: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorStop {
+    at: f32,
+    color: [f32; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DensityStop {
+    at: f32,
+    value: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GapDesc {
+    radius: f32,
+    width: f32,
+}
+
+fn default_time_scale() -> f32 { 0.9 }
+fn default_shadow_samples() -> u32 { 8 }
+fn default_mode() -> Mode { Mode::Kepler }
+fn default_gravity() -> f32 { 1.0 }
+fn default_dt() -> f32 { 0.002 }
+fn default_integrator() -> Integrator { Integrator::Leapfrog }
+fn default_camera_pos() -> [f32; 3] { [0.0, 0.0, -9.0] }
+fn default_fov() -> f32 { 1.0 }
+fn default_ring_opacity() -> f32 { 1.0 }
+fn default_ring_bands() -> f32 { 40.0 }
+fn default_band_contrast() -> f32 { 0.5 }
+fn default_cloud_altitude() -> f32 { 0.02 }
+fn default_rayleigh() -> [f32; 3] { [1.9, 4.4, 10.6] }
+fn default_rayleigh_height() -> f32 { 0.025 }
+fn default_mie() -> f32 { 3.0 }
+fn default_mie_height() -> f32 { 0.008 }
+fn default_mie_g() -> f32 { 0.76 }
+
+fn vec3(a: [f32; 3]) -> Vec3 { Vec3::new(a[0], a[1], a[2]) }
+
+/// Primer campo con algún valor `nan` o `inf` (TOML los admite como números).
+fn non_finite<'a>(fields: &[(&'a str, &[f32])]) -> Option<&'a str> {
+    fields.iter().find(|(_, v)| v.iter().any(|x| !x.is_finite())).map(|(name, _)| *name)
+}
+
+fn finite_error(field: &str) -> String {
+    format!("{} debe ser un número finito", field)
+}
+
+pub fn load(path: &Path, width: usize, height: usize) -> Result<Scene, SceneError> {
+    let origin = path.display().to_string();
+    let src = std::fs::read_to_string(path)
+        .map_err(|source| SceneError::Io { origin: origin.clone(), source })?;
+    parse(&src, &origin, width, height)
+}
+
+pub fn parse(src: &str, origin: &str, width: usize, height: usize) -> Result<Scene, SceneError> {
+    let desc: SceneDesc = toml::from_str(src)
+        .map_err(|source| SceneError::Parse { origin: origin.to_string(), source })?;
+    build(desc, origin, width, height)
+}
+
+fn build(desc: SceneDesc, origin: &str, width: usize, height: usize) -> Result<Scene, SceneError> {
+    let invalid = |entry: String, message: String| SceneError::Invalid {
+        origin: origin.to_string(),
+        entry,
+        message,
+    };
+
+    if !(desc.camera.fov > 0.0 && desc.camera.fov < std::f32::consts::PI) {
+        return Err(invalid("camera".into(), format!("fov debe estar entre 0 y pi (es {})", desc.camera.fov)));
+    }
+    let look_at = desc.camera.look_at.as_ref().map_or(&[][..], |v| v.as_slice());
+    if let Some(field) = non_finite(&[("position", &desc.camera.position), ("look_at", look_at)]) {
+        return Err(invalid("camera".into(), finite_error(field)));
+    }
+    let camera_pos = vec3(desc.camera.position);
+    let camera_target = desc.camera.look_at.map_or(camera_pos + Vec3::new(0.0, 0.0, 1.0), vec3);
+    if (camera_target - camera_pos).length() == 0.0 {
+        return Err(invalid("camera".into(), "look_at no puede coincidir con position".into()));
+    }
+    if !desc.time_scale.is_finite() {
+        return Err(invalid("time_scale".into(), "debe ser un número finito".into()));
+    }
+    if desc.render.shadow_samples == 0 {
+        return Err(invalid("render".into(), "shadow_samples debe ser al menos 1".into()));
+    }
+    let physics = &desc.physics;
+    if !(physics.dt > 0.0 && physics.dt.is_finite()) {
+        return Err(invalid("physics".into(), format!("dt debe ser positivo (es {})", physics.dt)));
+    }
+    if !(physics.gravity >= 0.0 && physics.gravity.is_finite()) {
+        return Err(invalid("physics".into(), format!("gravity no puede ser negativa (es {})", physics.gravity)));
+    }
+    if !(physics.softening >= 0.0 && physics.softening.is_finite()) {
+        return Err(invalid("physics".into(), format!("softening no puede ser negativo (es {})", physics.softening)));
+    }
+    if desc.bodies.is_empty() {
+        return Err(invalid("bodies".into(), "la escena no tiene cuerpos".into()));
+    }
+
+    let mut bodies: Vec<Body> = Vec::with_capacity(desc.bodies.len());
+
+    for (i, b) in desc.bodies.iter().enumerate() {
+        let entry = format!("bodies[{}] \"{}\"", i, b.name);
+
+        if b.name.is_empty() {
+            return Err(invalid(format!("bodies[{}]", i), "name no puede estar vacío".into()));
+        }
+        if desc.bodies[..i].iter().any(|o| o.name == b.name) {
+            return Err(invalid(entry, "nombre repetido".into()));
+        }
+        if !(b.radius > 0.0 && b.radius.is_finite()) {
+            return Err(invalid(entry, format!("radius debe ser positivo (es {})", b.radius)));
+        }
+        if !(b.mass >= 0.0 && b.mass.is_finite()) {
+            return Err(invalid(entry, format!("mass no puede ser negativa (es {})", b.mass)));
+        }
+        if let Some(field) = non_finite(&[
+            ("position", &b.position),
+            ("velocity", b.velocity.as_ref().map_or(&[], |v| v.as_slice())),
+            ("rotate_speed", b.rotate_speed.as_slice()),
+            ("obliquity", &[b.obliquity]),
+            ("obliquity_direction", &[b.obliquity_direction]),
+        ]) {
+            return Err(invalid(entry, finite_error(field)));
+        }
+
+        let parent = match &b.parent {
+            Some(name) if *name == b.name => {
+                return Err(invalid(entry, "un cuerpo no puede ser su propio padre".into()));
+            }
+            Some(name) => match desc.bodies.iter().position(|o| o.name == *name) {
+                Some(p) => Some(BodyId(p)),
+                None => return Err(invalid(entry, format!("parent \"{}\" no existe", name))),
+            },
+            None => None,
+        };
+
+        let orbit = match &b.orbit {
+            Some(_) if parent.is_none() => {
+                return Err(invalid(entry + ".orbit", "una órbita necesita un parent".into()));
+            }
+            Some(o) => {
+                if !(o.semi_major_axis > 0.0 && o.semi_major_axis.is_finite()) {
+                    return Err(invalid(entry + ".orbit", format!(
+                        "semi_major_axis debe ser positivo (es {})", o.semi_major_axis
+                    )));
+                }
+                if !(0.0..1.0).contains(&o.eccentricity) {
+                    return Err(invalid(entry + ".orbit", format!(
+                        "eccentricity debe estar en [0, 1) (es {})", o.eccentricity
+                    )));
+                }
+                if let Some(field) = non_finite(&[
+                    ("inclination", &[o.inclination]),
+                    ("ascending_node", &[o.ascending_node]),
+                    ("argument_of_periapsis", &[o.argument_of_periapsis]),
+                    ("mean_anomaly", &[o.mean_anomaly]),
+                ]) {
+                    return Err(invalid(entry + ".orbit", finite_error(field)));
+                }
+                let period = match o.period {
+                    Some(p) => p,
+                    None if physics.mode == Mode::Nbody => {
+                        // tercera ley de Kepler con la masa del padre
+                        let m = parent.map_or(0.0, |p| desc.bodies[p.0].mass) + b.mass;
+                        let mu = physics.gravity * m;
+                        if mu <= 0.0 {
+                            return Err(invalid(entry + ".orbit", "sin period, el padre necesita masa".into()));
+                        }
+                        std::f32::consts::TAU * (o.semi_major_axis.powi(3) / mu).sqrt()
+                    }
+                    None => return Err(invalid(entry + ".orbit", "falta period".into())),
+                };
+                if !(period > 0.0 && period.is_finite()) {
+                    return Err(invalid(entry + ".orbit", format!("period debe ser positivo (es {})", period)));
+                }
+                Some(Orbit {
+                    semi_major_axis: o.semi_major_axis,
+                    eccentricity: o.eccentricity,
+                    inclination: o.inclination.to_radians(),
+                    ascending_node: o.ascending_node.to_radians(),
+                    periapsis: o.argument_of_periapsis.to_radians(),
+                    mean_anomaly: o.mean_anomaly.to_radians(),
+                    period,
+                })
+            }
+            None => None,
+        };
+
+        let rotate_speed = match (b.rotate_speed, b.rotation_period) {
+            (Some(_), Some(_)) => {
+                return Err(invalid(entry, "usa rotate_speed o rotation_period, no ambos".into()));
+            }
+            (_, Some(p)) if !(p != 0.0 && p.is_finite()) => {
+                return Err(invalid(entry, format!("rotation_period debe ser finito y distinto de 0 (es {})", p)));
+            }
+            (_, Some(p)) => std::f32::consts::TAU / p,
+            (Some(w), None) => w,
+            (None, None) => 0.0,
+        };
+        if b.tidal_lock && parent.is_none() {
+            return Err(invalid(entry, "tidal_lock necesita un parent al que mirar".into()));
+        }
+        let mut sphere = Sphere::new(vec3(b.position), b.radius, b.kind, b.light, rotate_speed);
+        sphere.axis = Sphere::tilted_axis(b.obliquity.to_radians(), b.obliquity_direction.to_radians());
+        sphere.set_spin(0.0);
+        let seed = b.seed.unwrap_or_else(|| noise::derive_seed(desc.seed, &b.name));
+        sphere.noise = Noise::new(b.noise, seed);
+        if let Some(d) = b.crater_density {
+            if !(0.0..=1.0).contains(&d) {
+                return Err(invalid(entry, format!("crater_density debe estar entre 0 y 1, no {}", d)));
+            }
+            sphere.surface.crater_density = d;
+        }
+        for (field, value, target) in [
+            ("sea_level", b.sea_level, &mut sphere.surface.sea_level),
+            ("ice_caps", b.ice_caps, &mut sphere.surface.ice_caps),
+        ] {
+            match value {
+                Some(_) if !matches!(b.kind, SphereKind::Rocky) => {
+                    return Err(invalid(entry, format!("{} sólo se aplica a cuerpos rocky", field)));
+                }
+                Some(v) if !(0.0..=1.0).contains(&v) => {
+                    return Err(invalid(entry, format!("{} debe estar entre 0 y 1, no {}", field, v)));
+                }
+                Some(v) => *target = v,
+                None => {}
+            }
+        }
+        for (field, value, target) in [
+            ("ridges", b.ridges, &mut sphere.surface.ridges),
+            ("storms", b.storms, &mut sphere.surface.storms),
+            ("differential", b.differential, &mut sphere.surface.differential),
+            ("flow", b.flow, &mut sphere.surface.flow),
+            ("bump", b.bump, &mut sphere.surface.bump),
+            ("displacement", b.displacement, &mut sphere.surface.displacement),
+            ("city_lights", b.city_lights, &mut sphere.surface.city_lights),
+            ("lava", b.lava, &mut sphere.surface.lava),
+            ("aurora", b.aurora, &mut sphere.surface.aurora),
+        ] {
+            match value {
+                Some(v) if !(v >= 0.0 && v.is_finite()) => {
+                    return Err(invalid(entry, format!("{} no puede ser negativo: {}", field, v)));
+                }
+                Some(v) => *target = v,
+                None => {}
+            }
+        }
+        for (field, value, allowed, kinds) in [
+            ("displacement", sphere.surface.displacement, matches!(b.kind, SphereKind::Rocky | SphereKind::Moon), "rocky o moon"),
+            ("city_lights", sphere.surface.city_lights, matches!(b.kind, SphereKind::Rocky), "rocky"),
+            ("lava", sphere.surface.lava, matches!(b.kind, SphereKind::Rocky | SphereKind::Moon), "rocky o moon"),
+            ("aurora", sphere.surface.aurora, matches!(b.kind, SphereKind::Rocky | SphereKind::GasGiant), "rocky o gas_giant"),
+        ] {
+            if value > 0.0 && !allowed {
+                return Err(invalid(entry, format!("{} sólo se aplica a cuerpos {}", field, kinds)));
+            }
+        }
+
+        let ring = match &b.ring {
+            Some(r) => Some(build_ring(r, b.radius, sphere.axis).map_err(|m| invalid(entry.clone() + ".ring", m))?),
+            None => None,
+        };
+        let clouds = match &b.clouds {
+            Some(_) if !matches!(b.kind, SphereKind::Rocky | SphereKind::Moon) => {
+                return Err(invalid(entry + ".clouds", "las nubes sólo se aplican a cuerpos rocky o moon".into()));
+            }
+            Some(c) => {
+                let noise = Noise::new(b.noise, noise::derive_seed(seed, "nubes"));
+                Some(build_clouds(c, b.radius, &sphere, noise).map_err(|m| invalid(entry.clone() + ".clouds", m))?)
+            }
+            None => None,
+        };
+        let atmosphere = match &b.atmosphere {
+            Some(_) if b.light => {
+                return Err(invalid(entry + ".atmosphere", "una luz no puede tener atmósfera".into()));
+            }
+            Some(a) => Some(build_atmosphere(a, b.radius).map_err(|m| invalid(entry.clone() + ".atmosphere", m))?),
+            None => None,
+        };
+
+        bodies.push(Body {
+            name: b.name.clone(),
+            parent,
+            children: Vec::new(),
+            local: vec3(b.position),
+            orbit,
+            mass: b.mass,
+            velocity: b.velocity.map(vec3),
+            tidal_lock: b.tidal_lock,
+            ring,
+            clouds,
+            atmosphere,
+            sphere,
+        });
+    }
+
+    // la jerarquía tiene que ser un árbol: subiendo por los padres nunca se vuelve al inicio
+    for (i, b) in bodies.iter().enumerate() {
+        let mut steps = 0;
+        let mut cur = b.parent;
+        while let Some(BodyId(p)) = cur {
+            steps += 1;
+            if p == i || steps > bodies.len() {
+                return Err(invalid(
+                    format!("bodies[{}] \"{}\"", i, b.name),
+                    "la cadena de parent forma un ciclo".into(),
+                ));
+            }
+            cur = bodies[p].parent;
+        }
+    }
+    for i in 0..bodies.len() {
+        if let Some(BodyId(p)) = bodies[i].parent {
+            bodies[p].children.push(BodyId(i));
+        }
+    }
+
+    let mut scene = Scene {
+        width,
+        height,
+        camera_pos,
+        camera_target,
+        bodies,
+        objects: Vec::new(),
+        fov: desc.camera.fov,
+        time_scale: desc.time_scale,
+        shadow_samples: desc.render.shadow_samples,
+        physics: None,
+    };
+    if physics.mode == Mode::Nbody {
+        scene.enable_physics(physics.gravity, physics.dt, physics.softening, physics.integrator);
+    }
+    Ok(scene)
+}
+
+/// En el archivo los radios del anillo son múltiplos del radio del cuerpo.
+fn build_ring(r: &RingDesc, body_radius: f32, body_axis: Vec3) -> Result<Ring, String> {
+    if let Some(field) = non_finite(&[
+        ("outer", &[r.outer]),
+        ("tilt", r.tilt.as_slice()),
+        ("tilt_direction", &[r.tilt_direction]),
+        ("bands", &[r.bands]),
+    ]) {
+        return Err(finite_error(field));
+    }
+    if let Some(i) = r.colors.iter().position(|c| c.color.iter().any(|x| !x.is_finite())) {
+        return Err(finite_error(&format!("colors[{}].color", i)));
+    }
+    if let Some(i) = r.gaps.iter().position(|g| !g.radius.is_finite()) {
+        return Err(finite_error(&format!("gaps[{}].radius", i)));
+    }
+    if !(r.inner > 0.0 && r.inner < r.outer) {
+        return Err(format!("se requiere 0 < inner < outer (inner = {}, outer = {})", r.inner, r.outer));
+    }
+    if !(0.0..=1.0).contains(&r.opacity) {
+        return Err(format!("opacity debe estar en [0, 1] (es {})", r.opacity));
+    }
+    if !(0.0..=1.0).contains(&r.band_contrast) {
+        return Err(format!("band_contrast debe estar en [0, 1] (es {})", r.band_contrast));
+    }
+    let ats: Vec<f32> = r.colors.iter().map(|c| c.at).collect();
+    if let Some(i) = unsorted_stop(&ats) {
+        return Err(format!("colors[{}]: at debe estar en [0, 1] y en orden creciente", i));
+    }
+    let ats: Vec<f32> = r.density.iter().map(|d| d.at).collect();
+    if let Some(i) = unsorted_stop(&ats) {
+        return Err(format!("density[{}]: at debe estar en [0, 1] y en orden creciente", i));
+    }
+    if let Some(i) = r.density.iter().position(|d| !(0.0..=1.0).contains(&d.value)) {
+        return Err(format!("density[{}]: value debe estar en [0, 1]", i));
+    }
+    if let Some(i) = r.gaps.iter().position(|g| !(g.width > 0.0 && g.width.is_finite())) {
+        return Err(format!("gaps[{}]: width debe ser positivo", i));
+    }
+
+    Ok(Ring {
+        center: Vec3::zero(),
+        normal: match r.tilt {
+            Some(tilt) => Ring::tilt_normal(tilt.to_radians(), r.tilt_direction.to_radians()),
+            None => body_axis,
+        },
+        inner: r.inner * body_radius,
+        outer: r.outer * body_radius,
+        opacity: r.opacity,
+        colors: r.colors.iter().map(|c| (c.at, vec3(c.color))).collect(),
+        density: r.density.iter().map(|d| (d.at, d.value)).collect(),
+        bands: r.bands,
+        band_contrast: r.band_contrast,
+        gaps: r.gaps.iter()
+            .map(|g| RingGap { radius: g.radius * body_radius, width: g.width * body_radius })
+            .collect(),
+    })
+}
+
+fn build_clouds(c: &CloudsDesc, body_radius: f32, body: &Sphere, noise: Noise) -> Result<Clouds, String> {
+    if !(c.altitude > 0.0 && c.altitude.is_finite()) {
+        return Err(format!("altitude debe ser positiva (es {})", c.altitude));
+    }
+    let rotate_speed = c.rotate_speed.unwrap_or(0.8 * body.rotate_speed);
+    if !rotate_speed.is_finite() {
+        return Err(format!("rotate_speed inválida: {}", rotate_speed));
+    }
+    let mut clouds = Clouds::new(body_radius * (1.0 + c.altitude), body.axis, rotate_speed, noise);
+    for (field, value, target) in [
+        ("coverage", c.coverage, &mut clouds.coverage),
+        ("opacity", c.opacity, &mut clouds.opacity),
+    ] {
+        match value {
+            Some(v) if !(0.0..=1.0).contains(&v) => {
+                return Err(format!("{} debe estar en [0, 1] (es {})", field, v));
+            }
+            Some(v) => *target = v,
+            None => {}
+        }
+    }
+    if let Some(flow) = c.flow {
+        if !(flow >= 0.0 && flow.is_finite()) {
+            return Err(format!("flow no puede ser negativo: {}", flow));
+        }
+        clouds.flow = flow;
+    }
+    if let Some(color) = c.color {
+        if color.iter().any(|x| !x.is_finite()) {
+            return Err(finite_error("color"));
+        }
+        clouds.color = vec3(color);
+    }
+    Ok(clouds)
+}
+
+fn build_atmosphere(a: &AtmosphereDesc, body_radius: f32) -> Result<Atmosphere, String> {
+    let positive = |v: f32| v > 0.0 && v.is_finite();
+    let non_negative = |v: f32| v >= 0.0 && v.is_finite();
+    if !a.rayleigh.iter().all(|&c| non_negative(c)) || !non_negative(a.mie) {
+        return Err("los coeficientes rayleigh y mie no pueden ser negativos".into());
+    }
+    if !(positive(a.rayleigh_height) && positive(a.mie_height)) {
+        return Err(format!(
+            "rayleigh_height y mie_height deben ser positivos (son {} y {})", a.rayleigh_height, a.mie_height
+        ));
+    }
+    if !(a.mie_g > -1.0 && a.mie_g < 1.0) {
+        return Err(format!("mie_g debe estar en (-1, 1) (es {})", a.mie_g));
+    }
+    let thickness = a.thickness.unwrap_or(6.0 * a.rayleigh_height.max(a.mie_height));
+    if !positive(thickness) {
+        return Err(format!("thickness debe ser positivo (es {})", thickness));
+    }
+
+    Ok(Atmosphere {
+        center: Vec3::zero(),
+        planet_radius: body_radius,
+        radius: body_radius * (1.0 + thickness),
+        rayleigh: vec3(a.rayleigh) / body_radius,
+        mie: a.mie / body_radius,
+        rayleigh_height: a.rayleigh_height * body_radius,
+        mie_height: a.mie_height * body_radius,
+        mie_g: a.mie_g,
+    })
+}
+
+/// Índice de la primera parada fuera de [0, 1] o desordenada.
+fn unsorted_stop(ats: &[f32]) -> Option<usize> {
+    ats.iter().enumerate()
+        .position(|(i, &a)| !(0.0..=1.0).contains(&a) || (i > 0 && a < ats[i - 1]))
+}
@@ -1,17 +1,30 @@
 // src/sphere.rs
 use crate::vec3::Vec3;
 use crate::ray::Ray;
-use crate::noise::{fbm, noise3};
+use crate::noise::{hash1, smoothstep, Noise};
+use crate::crater::{craters, CraterSample};
+use crate::hittable::{Hit, Hittable};
+use serde::Deserialize;
 
 pub struct Sphere {
     pub center: Vec3,
     pub radius: f32,
     pub is_light: bool,
     pub kind: SphereKind,
-    pub rotate_speed: f32, 
+    /// spin rate in radians per unit of scene time
+    pub rotate_speed: f32,
+    /// unit spin axis (north pole)
+    pub axis: Vec3,
+    /// noise basis and seed used by every surface shader
+    pub noise: Noise,
+    pub surface: Surface,
+    /// body-fixed frame (prime meridian, axis, east) for the current spin angle,
+    /// updated by the scene every frame
+    frame: [Vec3; 3],
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SphereKind {
     Star,
     Rocky,
@@ -19,18 +32,297 @@ pub enum SphereKind {
     Moon,
 }
 
-pub struct Hit {
-    pub t: f32,
-    pub point: Vec3,
-    pub normal: Vec3,
-    pub kind: SphereKind,
+/// Per-body knobs for the procedural surface shaders.
+#[derive(Copy, Clone, Debug)]
+pub struct Surface {
+    /// chance that a crater cell holds a crater (0 = smooth surface)
+    pub crater_density: f32,
+    /// strength of ridged mountain ranges on rocky bodies
+    pub ridges: f32,
+    /// strength of swirling storms in gas giant bands
+    pub storms: f32,
+    /// extra angular speed lost towards the poles (rad per unit of time at the
+    /// pole, scaled by sin² of the latitude), so fluid bands shear
+    pub differential: f32,
+    /// how fast animated noise evolves (noise units per unit of time)
+    pub flow: f32,
+    /// multiplier on the slopes of the relief when bending the shading normal
+    /// (0 = flat shading, 1 = true slopes)
+    pub bump: f32,
+    /// if > 0, the relief (scaled by this) really displaces the surface and the
+    /// body is raymarched instead of intersected as a plain sphere
+    pub displacement: f32,
+    /// continent height (0..1) below which rocky bodies are covered by sea
+    /// (0 = dry world)
+    pub sea_level: f32,
+    /// fraction of each hemisphere, from the pole down, covered by ice
+    pub ice_caps: f32,
+    /// brightness of the night-side glows: towns on dry land, lava in the
+    /// cracks of volcanic worlds and auroral ovals around the poles
+    pub city_lights: f32,
+    pub lava: f32,
+    pub aurora: f32,
+}
+
+/// What a surface shader returns for a point.
+#[derive(Copy, Clone, Debug)]
+pub struct Shading {
     pub color: Vec3,
     pub emissive: Vec3,
+    /// shading normal, bent by the relief where there is any
+    pub normal: Vec3,
+    /// 1 over open water: the hit gets a sharp Fresnel glint instead of the
+    /// dull specular of rock
+    pub wet: f32,
+    /// self-lit features that only show where the star doesn't reach
+    pub night: Vec3,
+}
+
+/// Procedural relief at one point: height in body radii plus the pieces the
+/// shaders also use for color.
+struct Relief {
+    height: f32,
+    crater: CraterSample,
+    ridge: f32,
+    /// continent height (0..1) and how much of the point lies under the sea
+    elevation: f32,
+    wet: f32,
+}
+
+impl Surface {
+    /// Defaults that suit each kind of body.
+    pub fn for_kind(kind: SphereKind) -> Self {
+        let mut surface = Surface {
+            crater_density: 0.0, ridges: 0.0, storms: 0.0, differential: 0.0, flow: 0.0,
+            bump: 1.0, displacement: 0.0, sea_level: 0.0, ice_caps: 0.0,
+            city_lights: 0.0, lava: 0.0, aurora: 0.0,
+        };
+        match kind {
+            SphereKind::Rocky => {
+                surface.crater_density = 0.3;
+                surface.ridges = 0.6;
+            }
+            SphereKind::Moon => surface.crater_density = 0.6,
+            SphereKind::GasGiant => {
+                surface.storms = 0.6;
+                surface.differential = 0.25;
+                surface.flow = 0.05;
+            }
+            SphereKind::Star => {
+                surface.differential = 0.2;
+                surface.flow = 0.8;
+            }
+        }
+        surface
+    }
 }
 
 impl Sphere {
     pub fn new(center: Vec3, radius:f32, kind: SphereKind, is_light: bool, rotate_speed: f32) -> Self {
-        Sphere { center, radius, is_light, kind, rotate_speed }
+        let axis = Vec3::new(0.0, 1.0, 0.0);
+        let frame = [Vec3::new(1.0, 0.0, 0.0), axis, Vec3::new(0.0, 0.0, 1.0)];
+        Sphere { center, radius, is_light, kind, rotate_speed, axis, noise: Noise::default(), surface: Surface::for_kind(kind), frame }
+    }
+
+    /// Axis tilted `obliquity` radians away from +Y, leaning towards `direction`
+    /// (azimuth in the XZ plane).
+    pub fn tilted_axis(obliquity: f32, direction: f32) -> Vec3 {
+        Vec3::new(obliquity.sin() * direction.cos(), obliquity.cos(), obliquity.sin() * direction.sin())
+    }
+
+    /// Sets the rotation angle around the spin axis and rebuilds the body frame.
+    pub fn set_spin(&mut self, angle: f32) {
+        self.frame = spin_frame(self.axis, angle);
+    }
+
+    /// Spin angle that keeps the prime meridian facing `target` (tidal locking).
+    pub fn spin_facing(&self, target: Vec3) -> f32 {
+        let e1 = prime_meridian(self.axis);
+        let e2 = e1.cross(self.axis);
+        let d = target - self.center;
+        d.dot(e2).atan2(d.dot(e1))
+    }
+
+    /// Unit direction from the center to `p`, in the rotating body frame:
+    /// y is the spin axis, so `asin(y)` is the latitude.
+    pub fn to_local(&self, p: Vec3) -> Vec3 {
+        let d = (p - self.center).normalize();
+        Vec3::new(d.dot(self.frame[0]), d.dot(self.frame[1]), d.dot(self.frame[2]))
+    }
+
+    /// Body-frame direction back to world space (inverse of `to_local`).
+    pub fn from_local(&self, v: Vec3) -> Vec3 {
+        self.frame[0] * v.x + self.frame[1] * v.y + self.frame[2] * v.z
+    }
+
+    /// Ridged mountains plus craters, for rocky bodies and moons. The sea, if
+    /// any, floods the low continents and leaves a flat surface over them.
+    fn relief(&self, local: Vec3) -> Relief {
+        let crater = craters(self.noise, local, self.surface.crater_density);
+        let ridge = if self.surface.ridges > 0.0 { self.noise.ridged(local * 4.0, 5) } else { 0.0 };
+        let height = crater.height + self.surface.ridges * 0.015 * ridge;
+        if self.surface.sea_level <= 0.0 {
+            return Relief { height, crater, ridge, elevation: 1.0, wet: 0.0 };
+        }
+        // large-scale continents, offset so they don't line up with the ridges
+        let elevation = self.noise.fbm(local * 1.5 + Vec3::new(7.3, 1.9, 4.1), 5) / 1.9375;
+        let wet = 1.0 - smoothstep(self.surface.sea_level - 0.01, self.surface.sea_level, elevation);
+        Relief { height: height * (1.0 - wet), crater, ridge, elevation, wet }
+    }
+
+    /// Polar ice cover at `local`, 0..1, with a ragged edge.
+    fn ice(&self, local: Vec3) -> f32 {
+        if self.surface.ice_caps <= 0.0 {
+            return 0.0;
+        }
+        let edge = 1.0 - self.surface.ice_caps + 0.08 * self.noise.signed(local * 6.0);
+        smoothstep(edge - 0.015, edge + 0.015, local.y.abs())
+    }
+
+    /// Towns on dry land: a point of light per small cell, only inside the
+    /// populated regions, over a faint glow of suburbs.
+    fn city_lights(&self, local: Vec3, land: f32) -> Vec3 {
+        if self.surface.city_lights <= 0.0 || land <= 0.0 {
+            return Vec3::zero();
+        }
+        let people = self.noise.fbm(local * 4.0 + Vec3::new(3.1, 8.7, 2.3), 3) / 1.75;
+        let populated = smoothstep(0.52, 0.65, people);
+        if populated <= 0.0 {
+            return Vec3::zero();
+        }
+        let towns = self.noise.worley(local * 80.0);
+        let size = 0.15 + 0.25 * hash1(towns.id);
+        let town = (1.0 - towns.f1 / size).max(0.0);
+        Vec3::new(1.0, 0.72, 0.38) * (self.surface.city_lights * land * populated * (0.03 + 0.6 * town * town))
+    }
+
+    /// Molten rock glowing along the edges of a cellular crust.
+    fn lava(&self, local: Vec3) -> Vec3 {
+        if self.surface.lava <= 0.0 {
+            return Vec3::zero();
+        }
+        let cells = self.noise.worley(local * 7.0);
+        let crack = 1.0 - smoothstep(0.0, 0.08, cells.f2 - cells.f1);
+        let heat = 0.4 + 0.6 * self.noise.sample(local * 3.0 + Vec3::new(5.5, 0.7, 9.2));
+        Vec3::new(1.0, 0.32, 0.05) * (self.surface.lava * crack * heat * 1.5)
+    }
+
+    /// Auroral ovals about 20° from each pole, with curtains that ripple in time.
+    fn aurora(&self, local: Vec3, time: f32) -> Vec3 {
+        if self.surface.aurora <= 0.0 {
+            return Vec3::zero();
+        }
+        let colatitude = local.y.abs().min(1.0).acos();
+        let wobble = 0.06 * (2.0 * self.noise.sample4(local * 5.0, time * 0.3) - 1.0);
+        let d = (colatitude - 0.35 - wobble) / 0.05;
+        // green oxygen line, with a fainter red fringe on the poleward side
+        let color = Vec3::new(0.15, 1.0, 0.45) * (-d * d).exp()
+            + Vec3::new(0.9, 0.2, 0.4) * (0.25 * (-(d + 1.5) * (d + 1.5)).exp());
+        // curtains: streaks that only vary along the oval
+        let lon = local.z.atan2(local.x);
+        let streaks = Vec3::new(lon.cos(), lon.sin(), local.y.signum()) * 12.0;
+        let curtains = self.noise.sample4(streaks, time * 0.8);
+        color * (self.surface.aurora * (0.3 + 0.9 * curtains) * 0.6)
+    }
+
+    /// World-space normal of the displaced surface `local * (1 + height)`, from
+    /// finite differences of the relief along two tangents. Slopes facing away
+    /// from a star go dark before the geometric terminator does.
+    fn relief_normal(&self, local: Vec3, height: f32) -> Vec3 {
+        // smaller than the smallest crater (about 5e-3 radii)
+        const EPS: f32 = 5e-4;
+        if self.surface.bump <= 0.0 {
+            return self.from_local(local);
+        }
+        let up = if local.y.abs() < 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let t1 = up.cross(local).normalize();
+        let t2 = local.cross(t1);
+        let dh1 = (self.relief((local + t1 * EPS).normalize()).height - height) / EPS;
+        let dh2 = (self.relief((local + t2 * EPS).normalize()).height - height) / EPS;
+        // displaced terrain really has its slopes multiplied by the displacement
+        let slope = self.surface.bump * if self.displaced() { self.surface.displacement } else { 1.0 };
+        let n = local - (t1 * dh1 + t2 * dh2) * slope;
+        self.from_local(n.normalize())
+    }
+
+    /// Differential rotation of a fluid surface: the further from the equator,
+    /// the more `local` lags behind the rigid spin, by `differential * sin²(lat) * time`.
+    fn sheared(&self, local: Vec3, time: f32) -> Vec3 {
+        let lag = self.surface.differential * local.y * local.y * time;
+        let (s, c) = lag.sin_cos();
+        Vec3::new(local.x * c - local.z * s, local.y, local.x * s + local.z * c)
+    }
+
+    fn displaced(&self) -> bool {
+        self.surface.displacement > 0.0
+    }
+
+    /// Largest |relief height| in body radii. The relief is clamped to it in the
+    /// terrain SDF, so the surface always lies between the two bounding spheres.
+    fn relief_bound(&self) -> f32 {
+        0.03 + 0.015 * self.surface.ridges
+    }
+
+    /// Radial distance from `p` to the displaced surface (negative below it).
+    /// Not a true distance, so the march takes conservative steps.
+    fn terrain_sdf(&self, p: Vec3) -> f32 {
+        let bound = self.relief_bound();
+        let h = self.relief(self.to_local(p)).height.clamp(-bound, bound);
+        (p - self.center).length() - self.radius * (1.0 + self.surface.displacement * h)
+    }
+
+    /// Raymarches the displaced terrain within the shell between the inner and
+    /// outer bounding spheres. Rays that miss the outer sphere cost a single
+    /// analytic test, and a ray that reaches the inner sphere must have crossed
+    /// the surface before it, which bounds the march.
+    fn march(&self, ray: &Ray, t_max: f32) -> Option<f32> {
+        const MAX_STEPS: usize = 160;
+        const HIT_EPS: f32 = 1e-4;
+        let extra = self.surface.displacement * self.relief_bound() * self.radius;
+        let min_step = 1e-3 * self.radius;
+
+        let (near, far) = sphere_span(ray, self.center, self.radius + extra)?;
+        let mut end = far.min(t_max);
+        if let Some((inner, _)) = sphere_span(ray, self.center, self.radius - extra) {
+            if inner > 0.0 {
+                end = end.min(inner);
+            }
+        }
+        let mut t = near.max(0.001);
+        if t >= end {
+            return None;
+        }
+        let mut f = self.terrain_sdf(ray.at(t));
+        if f < 0.0 {
+            // the ray starts below the terrain: nothing to see from in there
+            return None;
+        }
+
+        for _ in 0..MAX_STEPS {
+            if f < HIT_EPS {
+                return Some(t);
+            }
+            let next = (t + (0.8 * f).max(min_step)).min(end);
+            let f_next = self.terrain_sdf(ray.at(next));
+            if f_next < 0.0 {
+                return Some(self.refine(ray, t, next));
+            }
+            if next >= end {
+                return None;
+            }
+            t = next;
+            f = f_next;
+        }
+        None
+    }
+
+    /// Bisection between a point above the terrain (`a`) and one below it (`b`).
+    fn refine(&self, ray: &Ray, mut a: f32, mut b: f32) -> f32 {
+        for _ in 0..10 {
+            let mid = 0.5 * (a + b);
+            if self.terrain_sdf(ray.at(mid)) < 0.0 { b = mid; } else { a = mid; }
+        }
+        a
     }
 
     pub fn intersect(&self, ray: &Ray) -> Option<f32> {
@@ -47,8 +339,12 @@ impl Sphere {
         Some(t)
     }
 
-    pub fn shade(&self, p: Vec3, normal: Vec3, time: f32) -> (Vec3, Vec3) {
-        // returns (diffuse_color, emissive)
+    /// Emission of the sphere seen as a light source, evaluated at its center.
+    pub fn emission(&self, time: f32) -> Vec3 {
+        self.shade(self.center, Vec3::new(0.0, 1.0, 0.0), time).emissive
+    }
+
+    pub fn shade(&self, p: Vec3, normal: Vec3, time: f32) -> Shading {
         match self.kind {
             SphereKind::Star => self.shader_star(p, normal, time),
             SphereKind::Rocky => self.shader_rocky(p, normal, time),
@@ -57,85 +353,147 @@ impl Sphere {
         }
     }
 
-    fn shader_star(&self, p: Vec3, _n: Vec3, time: f32) -> (Vec3, Vec3) {
+    fn shader_star(&self, p: Vec3, n: Vec3, time: f32) -> Shading {
         // radial emissive gradient + subtle noise rays
-        let to_center = (p - self.center).normalize();
         let r = (p - self.center).length() / self.radius;
         // radial falloff
         let glow = (1.0 - r).max(0.0).powf(1.5);
-        // base color yellowish with flicker noise
-        let t = time * 0.8;
-        let flick = 0.8 + 0.4 * noise3(Vec3::new(p.x*3.0 + t, p.y*3.0, p.z*3.0));
+        // base color yellowish with flicker noise that evolves in place,
+        // carried along by the differential rotation
+        let local = self.sheared(self.to_local(p), time);
+        let flick = 0.8 + 0.4 * self.noise.sample4(local * 3.0, time * self.surface.flow);
         let emissive = Vec3::new(1.0, 0.85, 0.5) * glow * flick * 2.5;
         // visible surface color (tiny)
         let surf = Vec3::new(1.0, 0.9, 0.6) * (0.4 + 0.6*glow);
-        (surf, emissive)
+        Shading { color: surf, emissive, normal: n, wet: 0.0, night: Vec3::zero() }
     }
 
-    fn shader_rocky(&self, p: Vec3, n: Vec3, time: f32) -> (Vec3, Vec3) {
-        // compute spherical coords (latitude / longitude) relative to center
-        let local = (p - self.center).normalize();
+    fn shader_rocky(&self, p: Vec3, _n: Vec3, time: f32) -> Shading {
+        // compute spherical coords (latitude / longitude) in the rotating body frame
+        let local = self.to_local(p);
         let lat = local.y.asin(); // -pi/2..pi/2
         let lon = local.z.atan2(local.x);
         let mut base = Vec3::new(0.32, 0.24, 0.18); // rock base
         // layer 1: color variation by fbm
-        let h = fbm(local * 3.0 + Vec3::new(time*0.05,0.0,0.0), 4) * 0.5;
+        let h = self.noise.fbm(local * 3.0, 4) * 0.5;
         base = base + Vec3::new(h*0.15, h*0.1, h*0.05);
         // layer 2: bands / continents using sin lat+lon
-        let band = ( (lat*6.0 + lon*2.0 + time*0.2).sin() * 0.5 + 0.5 ).powf(1.3);
+        let band = ( (lat*6.0 + lon*2.0).sin() * 0.5 + 0.5 ).powf(1.3);
         base = base * (0.7 + 0.6*band);
-        // layer 3: craters: place darker circular spots using noise and threshold
-        let crater_noise = noise3(local * 12.0);
-        let mut crater_mask = 0.0;
-        if crater_noise > 0.7 {
-            crater_mask = (crater_noise - 0.7) / 0.3;
-        }
-        let crater_color = base * 0.5;
-        let color = base*(1.0-crater_mask) + crater_color*crater_mask;
-        // small specular highlight
-        let rim = (1.0 - (n.dot(Vec3::new(0.0,1.0,0.0))).abs()).powf(3.0) * 0.2;
-        let final_color = color + Vec3::new(0.05,0.05,0.06) * rim;
-        (final_color, Vec3::new(0.0,0.0,0.0))
+        // mountain ranges: ridged multifractal lightens the crests
+        let relief = self.relief(local);
+        base = base * (1.0 + self.surface.ridges * (0.8 * relief.ridge - 0.25));
+        // layer 3: impact craters (darker floors, bright rims and ejecta)
+        let land = base * relief.crater.albedo;
+        // sea: darker and bluer the deeper it gets below sea level
+        let depth = ((self.surface.sea_level - relief.elevation) / 0.08).clamp(0.0, 1.0);
+        let water = Vec3::new(0.05, 0.2, 0.26) * (1.0 - depth) + Vec3::new(0.01, 0.04, 0.12) * depth;
+        let color = land * (1.0 - relief.wet) + water * relief.wet;
+        // polar caps cover land and sea alike
+        let ice = self.ice(local);
+        let color = color * (1.0 - ice) + Vec3::new(0.88, 0.92, 0.96) * ice;
+        // mountains and craters bend the normal so they catch the light
+        let normal = self.relief_normal(local, relief.height);
+        // glows that only show on the night side
+        let night = self.city_lights(local, (1.0 - relief.wet) * (1.0 - ice))
+            + self.lava(local) * (1.0 - relief.wet)
+            + self.aurora(local, time);
+        Shading { color, emissive: Vec3::zero(), normal, wet: relief.wet * (1.0 - ice), night }
     }
 
-    fn shader_gas(&self, p: Vec3, n: Vec3, time: f32) -> (Vec3, Vec3) {
-    let local = (p - self.center).normalize();
-    let lat = local.y;
+    fn shader_gas(&self, p: Vec3, n: Vec3, time: f32) -> Shading {
+    // rotación diferencial: las bandas se cizallan y las tormentas evolucionan en su sitio
+    let local = self.sheared(self.to_local(p), time);
+    let w = time * self.surface.flow;
+    let storms = self.surface.storms;
+
+    // Tormentas: el fbm deformado ondula los bordes de las bandas
+    let warp = self.noise.warped4(local * 2.5, w, 4, 1.2) - 1.0;
+    let lat = local.y + storms * 0.2 * warp;
 
     // Bandas atmosféricas
     let base = Vec3::new(0.45, 0.55, 0.85);
-    let band = 0.5 + 0.5 * (lat * 10.0 + time * 0.5).sin();
+    let band = 0.5 + 0.5 * (lat * 10.0).sin();
     let band_color = base * (0.6 + 0.8 * band);
 
-    // Swirls
-    let swirl = fbm(local * 6.0 + Vec3::new(0.0, time*0.3, 0.0), 5) * 0.25;
-    let color = band_color + Vec3::new(0.05, 0.08, 0.12) * swirl;
+    // Swirls, con vórtices más marcados donde hay turbulencia
+    let swirl = self.noise.fbm4(local * 6.0, w, 5) * 0.25;
+    let eddies = self.noise.turbulence4(Vec3::new(local.x * 4.0, local.y * 12.0, local.z * 4.0), w, 4);
+    let color = band_color + Vec3::new(0.05, 0.08, 0.12) * swirl
+        + Vec3::new(0.18, 0.14, 0.08) * (storms * (eddies - 0.3));
 
-    // ANILLOS PROCEDURALES
-    let rp = p - self.center;
-    let dist = (rp.x*rp.x + rp.z*rp.z).sqrt();
+    // auroras: siguen al eje magnético, no a las bandas cizalladas
+    let night = self.aurora(self.to_local(p), time);
 
-    // Radio posible del anillo alrededor del gas giant
-    let inner = self.radius * 1.4;
-    let outer = self.radius * 2.4;
+    Shading { color, emissive: Vec3::zero(), normal: n, wet: 0.0, night }
+}
 
-    let mut ring = 0.0;
-    if dist > inner && dist < outer {
-        ring = ((dist - inner) / (outer - inner) * 30.0).sin().abs();
+    fn shader_moon(&self, p: Vec3, _n: Vec3, _time: f32) -> Shading {
+        // gray with mottled maria and impact craters
+        let local = self.to_local(p);
+        let base = Vec3::new(0.7, 0.7, 0.75);
+        let noise = self.noise.fbm(local * 10.0, 4);
+        let relief = self.relief(local);
+        let color = base * (0.6 + 0.6*noise) * relief.crater.albedo;
+        let normal = self.relief_normal(local, relief.height);
+        Shading { color, emissive: Vec3::zero(), normal, wet: 0.0, night: self.lava(local) }
     }
+}
 
-    let ring_color = Vec3::new(0.85, 0.8, 0.7) * ring;
-    let final_color = color + ring_color * 1.5;
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, t_max: f32, time: f32) -> Option<Hit> {
+        let t = self.distance(ray, t_max)?;
+        let point = ray.at(t);
+        let shading = self.shade(point, (point - self.center).normalize(), time);
+        // water: Schlick's Fresnel (F0 = 0.02), much stronger at grazing angles,
+        // on a normalized Blinn-Phong lobe so the tight highlight keeps its energy
+        const WATER_SHININESS: f32 = 400.0;
+        let cos = shading.normal.dot(-ray.dir).max(0.0);
+        let fresnel = 0.02 + 0.98 * (1.0 - cos).powi(5);
+        let glint = fresnel * (WATER_SHININESS + 8.0) / 8.0;
+        Some(Hit {
+            t,
+            point,
+            normal: shading.normal,
+            color: shading.color,
+            emissive: shading.emissive,
+            night: shading.night,
+            specular: 0.2 + (glint - 0.2) * shading.wet,
+            shininess: 40.0 + (WATER_SHININESS - 40.0) * shading.wet,
+            opacity: 1.0,
+        })
+    }
 
-    (final_color, Vec3::zero())
+    fn distance(&self, ray: &Ray, t_max: f32) -> Option<f32> {
+        if self.displaced() {
+            return self.march(ray, t_max);
+        }
+        self.intersect(ray).filter(|&t| t < t_max)
+    }
 }
 
-    fn shader_moon(&self, p: Vec3, n: Vec3, _time: f32) -> (Vec3, Vec3) {
-        // simple gray with crater noise
-        let local = (p - self.center).normalize();
-        let base = Vec3::new(0.7, 0.7, 0.75);
-        let noise = fbm(local * 10.0, 4);
-        let color = base * (0.6 + 0.6*noise);
-        (color, Vec3::zero())
-    }
+/// Reference direction for longitude 0 at spin 0: the world X axis projected
+/// onto the equator (or Z when the axis lies along X).
+fn prime_meridian(axis: Vec3) -> Vec3 {
+    let r = if axis.x.abs() > 0.9 { Vec3::new(0.0, 0.0, 1.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+    (r - axis * r.dot(axis)).normalize()
+}
+
+/// Frame (prime meridian, axis, east) of a body spun `angle` radians around `axis`.
+pub(crate) fn spin_frame(axis: Vec3, angle: f32) -> [Vec3; 3] {
+    let e1 = prime_meridian(axis);
+    let e2 = e1.cross(axis);
+    let (s, c) = angle.sin_cos();
+    [e1 * c + e2 * s, axis, e2 * c - e1 * s]
+}
+
+/// Entry and exit distances of a ray through a sphere (either may be negative).
+pub(crate) fn sphere_span(ray: &Ray, center: Vec3, radius: f32) -> Option<(f32, f32)> {
+    let oc = ray.orig - center;
+    let b = oc.dot(ray.dir);
+    let c = oc.dot(oc) - radius * radius;
+    let disc = b * b - c;
+    if disc < 0.0 { return None; }
+    let sq = disc.sqrt();
+    Some((-b - sq, -b + sq))
 }
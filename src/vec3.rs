@@ -26,7 +26,7 @@ impl Vec3 {
     }
     pub fn mul_scalar(self, s: f32) -> Self { Self::new(self.x*s, self.y*s, self.z*s) }
     pub fn clamp(self, a: f32, b: f32) -> Self {
-        let c = |v:f32| v.max(a).min(b);
+        let c = |v:f32| v.clamp(a, b);
         Self::new(c(self.x), c(self.y), c(self.z))
     }
 }
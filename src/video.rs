@@ -0,0 +1,103 @@
+// src/video.rs
+// Salida de vídeo en flujo continuo (YUV4MPEG2 o RGB crudo) para encoders externos:
+//   Espacio export -o - --size 1920x1080 --frames 3600 | ffmpeg -i - orbitas.mp4
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::image_out;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StreamFormat {
+    /// YUV4MPEG2 4:4:4; lleva tamaño y fps en la cabecera, ffmpeg no necesita más
+    Y4m,
+    /// RGB24 sin cabecera: `ffmpeg -f rawvideo -pix_fmt rgb24 -s WxH -r FPS -i -`
+    Rgb,
+}
+
+impl FromStr for StreamFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "y4m" => Ok(StreamFormat::Y4m),
+            "rgb" | "raw" => Ok(StreamFormat::Rgb),
+            _ => Err(format!("formato de vídeo desconocido: {} (usa y4m o rgb)", s)),
+        }
+    }
+}
+
+/// Escribe frames uno a uno sobre cualquier `Write` (archivo, tubería o stdout).
+pub struct VideoWriter<W: Write> {
+    out: W,
+    format: StreamFormat,
+    width: usize,
+    height: usize,
+    planes: Vec<u8>,
+}
+
+impl<W: Write> VideoWriter<W> {
+    /// Escribe la cabecera (en Y4M) y deja el flujo listo para `write_frame`.
+    pub fn new(mut out: W, format: StreamFormat, width: usize, height: usize, fps: f32) -> io::Result<Self> {
+        if format == StreamFormat::Y4m {
+            let (num, den) = fps_ratio(fps);
+            writeln!(out, "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C444", width, height, num, den)?;
+        }
+        Ok(VideoWriter { out, format, width, height, planes: Vec::new() })
+    }
+
+    pub fn write_frame(&mut self, buffer: &[u32]) -> io::Result<()> {
+        debug_assert_eq!(buffer.len(), self.width * self.height);
+        match self.format {
+            StreamFormat::Rgb => self.out.write_all(&image_out::to_rgb8(buffer)),
+            StreamFormat::Y4m => {
+                self.out.write_all(b"FRAME\n")?;
+                to_yuv444(buffer, &mut self.planes);
+                self.out.write_all(&self.planes)
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Fps como fracción entera: 30 -> 30:1, 29.97 -> 2997:100.
+fn fps_ratio(fps: f32) -> (u32, u32) {
+    let num = (fps as f64 * 1000.0).round().max(1.0) as u32;
+    let (mut a, mut b) = (num, 1000u32);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    (num / a, 1000 / a)
+}
+
+/// RGB -> Y'CbCr BT.601 de rango limitado (lo que ffmpeg asume si la cabecera
+/// no dice otra cosa), en tres planos completos Y, U, V.
+fn to_yuv444(buffer: &[u32], planes: &mut Vec<u8>) {
+    let n = buffer.len();
+    planes.resize(3 * n, 0);
+    let (y, uv) = planes.split_at_mut(n);
+    let (u, v) = uv.split_at_mut(n);
+    for (i, &p) in buffer.iter().enumerate() {
+        let r = ((p >> 16) & 0xff) as f32;
+        let g = ((p >> 8) & 0xff) as f32;
+        let b = (p & 0xff) as f32;
+        y[i] = (16.0 + 0.256_788 * r + 0.504_129 * g + 0.097_906 * b).round() as u8;
+        u[i] = (128.0 - 0.148_223 * r - 0.290_993 * g + 0.439_216 * b).round() as u8;
+        v[i] = (128.0 + 0.439_216 * r - 0.367_788 * g - 0.071_427 * b).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fps_ratio_reduces_the_fraction() {
+        assert_eq!(fps_ratio(30.0), (30, 1));
+        assert_eq!(fps_ratio(29.97), (2997, 100));
+        assert_eq!(fps_ratio(23.976), (2997, 125));
+        assert_eq!(fps_ratio(12.5), (25, 2));
+    }
+}